@@ -19,6 +19,7 @@ fn main() {
         .allowlist_function("archive_entry_filetype")
         .allowlist_function("archive_entry_pathname")
         .allowlist_function("archive_entry_paths")
+        .allowlist_function("archive_entry_size")
         .allowlist_function("archive_read_new")
         .allowlist_function("archive_read_support_format_all")
         .allowlist_function("archive_read_open_filename")
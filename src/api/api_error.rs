@@ -1,26 +1,73 @@
 use crate::cache::CacheError;
+use crate::util::time;
+use reqwest::header::InvalidHeaderValue;
 use std::error::Error;
 use std::fmt;
 use std::num::ParseIntError;
+use std::path::PathBuf;
 use tokio::io;
 use tokio::task::JoinError;
 use tokio_tungstenite::tungstenite;
 use url::ParseError;
 
+// The larger payloads are boxed, so that every Result<_, ApiError> doesn't have to make room for them
 #[derive(Debug)]
 pub enum ApiError {
     ApiKeyMissing,
     ConnectionError { source: reqwest::Error },
     CacheError { source: CacheError },
+    CoalescedError { message: String },
     Expired,
+    GraphQlError { message: String },
     HashMismatch,
     IOError { source: io::Error },
+    InvalidHeader { source: InvalidHeaderValue },
+    InvalidNxmUrl,
     IsUnitTest,
     JoinError { source: JoinError },
+    Offline,
     ParseError { source: ParseError },
     ParseIntError { source: ParseIntError },
+    ProxyError { message: String },
+    RateLimited { retry_after: u64 }, // unix timestamp
     SerializationError { source: serde_json::Error },
-    WebsocketError { source: tungstenite::Error },
+    Unauthorized,
+    UnexpectedResponse { response: Box<RawResponse> },
+    UnknownGame { game: String },
+    WebsocketError { source: Box<tungstenite::Error> },
+}
+
+// A response that couldn't be used, along with where it was saved in full
+#[derive(Clone, Debug)]
+pub struct RawResponse {
+    pub endpoint: String,
+    pub status: u16,
+    pub excerpt: String,
+    pub saved_to: Option<PathBuf>,
+}
+
+impl ApiError {
+    /* Copies the error for the other waiters of a coalesced request. Errors that callers check for are kept as they
+     * are, while errors with a source that can't be cloned are only kept as their message. */
+    pub fn duplicate(&self) -> Self {
+        match self {
+            ApiError::ApiKeyMissing => ApiError::ApiKeyMissing,
+            ApiError::Expired => ApiError::Expired,
+            ApiError::HashMismatch => ApiError::HashMismatch,
+            ApiError::InvalidNxmUrl => ApiError::InvalidNxmUrl,
+            ApiError::IsUnitTest => ApiError::IsUnitTest,
+            ApiError::Offline => ApiError::Offline,
+            ApiError::RateLimited { retry_after } => ApiError::RateLimited {
+                retry_after: *retry_after,
+            },
+            ApiError::Unauthorized => ApiError::Unauthorized,
+            ApiError::UnexpectedResponse { response } => ApiError::UnexpectedResponse {
+                response: response.clone(),
+            },
+            ApiError::UnknownGame { game } => ApiError::UnknownGame { game: game.clone() },
+            e => ApiError::CoalescedError { message: e.to_string() },
+        }
+    }
 }
 
 impl Error for ApiError {
@@ -31,6 +78,7 @@ impl Error for ApiError {
             ApiError::Expired => None,
             ApiError::HashMismatch => None,
             ApiError::IOError { ref source } => Some(source),
+            ApiError::InvalidHeader { ref source } => Some(source),
             ApiError::JoinError { ref source } => Some(source),
             ApiError::ParseError { ref source } => Some(source),
             ApiError::ParseIntError { ref source } => Some(source),
@@ -45,15 +93,41 @@ impl fmt::Display for ApiError {
         match self {
             ApiError::ApiKeyMissing => f.write_str("No apikey configured. API connections are disabled."),
             ApiError::CacheError { source } => source.fmt(f),
+            ApiError::CoalescedError { message } => f.write_str(message),
             ApiError::ConnectionError { source } => source.fmt(f),
             ApiError::Expired => f.write_str("Download link is expired."),
+            ApiError::GraphQlError { message } => write!(f, "The Nexus was unable to answer the query: {message}"),
             ApiError::HashMismatch => f.write_str("Downloaded file has seemingly incorrect hash."),
             ApiError::IOError { source } => source.fmt(f),
             ApiError::JoinError { source } => source.fmt(f),
             ApiError::SerializationError { source } => source.fmt(f),
+            ApiError::InvalidHeader { source } => write!(f, "Invalid request header: {source}"),
+            ApiError::InvalidNxmUrl => f.write_str("Unrecognized nxm url format."),
             ApiError::IsUnitTest => f.write_str("Unit tests aren't allowed to make network connections."),
+            ApiError::Offline => f.write_str("Offline mode is on, the Nexus isn't contacted."),
             ApiError::ParseError { source } => source.fmt(f),
             ApiError::ParseIntError { source } => source.fmt(f),
+            ApiError::ProxyError { message } => write!(f, "Unable to connect through the proxy: {message}"),
+            ApiError::RateLimited { retry_after } => write!(
+                f,
+                "API rate limit exceeded. Requests are paused for {}.",
+                time::human_readable_duration(retry_after.saturating_sub(time::now()))
+            ),
+            ApiError::Unauthorized => {
+                f.write_str("The Nexus rejected the API key, going offline. Restart dmodman to create a new one.")
+            }
+            ApiError::UnexpectedResponse { response } => {
+                write!(
+                    f,
+                    "Unexpected response ({}) from {}: \"{}\"",
+                    response.status, response.endpoint, response.excerpt
+                )?;
+                match &response.saved_to {
+                    Some(path) => write!(f, " Saved to {}.", path.display()),
+                    None => Ok(()),
+                }
+            }
+            ApiError::UnknownGame { game } => write!(f, "The Nexus doesn't have a game called \"{game}\"."),
             ApiError::WebsocketError { source } => source.fmt(f),
         }
     }
@@ -77,6 +151,12 @@ impl From<io::Error> for ApiError {
     }
 }
 
+impl From<InvalidHeaderValue> for ApiError {
+    fn from(error: InvalidHeaderValue) -> Self {
+        ApiError::InvalidHeader { source: error }
+    }
+}
+
 impl From<reqwest::Error> for ApiError {
     fn from(error: reqwest::Error) -> Self {
         ApiError::ConnectionError { source: error }
@@ -103,6 +183,8 @@ impl From<ParseIntError> for ApiError {
 
 impl From<tungstenite::Error> for ApiError {
     fn from(error: tungstenite::Error) -> Self {
-        ApiError::WebsocketError { source: error }
+        ApiError::WebsocketError {
+            source: Box::new(error),
+        }
     }
 }
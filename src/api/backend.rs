@@ -0,0 +1,36 @@
+use super::ApiError;
+use reqwest::{Method, RequestBuilder};
+use url::Url;
+
+/* The Client sends its requests through a Backend. This lets tests swap the Nexus for a local server, see
+ * api/mock_server.rs. */
+pub trait Backend: Send + Sync {
+    // Base URL for API requests. Endpoints are joined to it, so it needs to end with a '/'.
+    fn api_url(&self) -> &Url;
+
+    fn request(&self, method: Method, url: Url) -> Result<RequestBuilder, ApiError>;
+}
+
+pub struct NexusBackend {
+    client: reqwest::Client,
+    api_url: Url,
+}
+
+impl NexusBackend {
+    pub fn new(api_url: Url, client: reqwest::Client) -> Self {
+        Self { client, api_url }
+    }
+}
+
+impl Backend for NexusBackend {
+    fn api_url(&self) -> &Url {
+        &self.api_url
+    }
+
+    fn request(&self, method: Method, url: Url) -> Result<RequestBuilder, ApiError> {
+        if cfg!(test) {
+            return Err(ApiError::IsUnitTest);
+        }
+        Ok(self.client.request(method, url))
+    }
+}
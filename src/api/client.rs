@@ -1,31 +1,45 @@
+use super::backend::{Backend, NexusBackend};
+use super::offline_mode::OfflineMode;
 use super::request_counter::RequestCounter;
-use super::ApiError;
-use crate::config::Config;
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
-use reqwest::Response;
+use super::{ApiError, RawResponse};
+use crate::cache::Validators;
+use crate::config::{Config, Network};
+use crate::util::time;
+use reqwest::header::{HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, USER_AGENT};
+use reqwest::{Certificate, Method, Proxy, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::fs;
 use url::Url;
 
+// Length of the part of an unexpected response that's shown in the error message
+const EXCERPT_LEN: usize = 200;
+
 /* API reference:
  * https://app.swaggerhub.com/apis-docs/NexusMods/nexus-mods_public_api_params_in_form_data/1.0
  */
 
-const API_URL: &str = "https://api.nexusmods.com/v1/";
-
 #[derive(Clone)]
 pub struct Client {
-    client: reqwest::Client,
+    backend: Arc<dyn Backend>,
     headers: Arc<HeaderMap>,
     api_headers: Arc<Option<HeaderMap>>,
+    responses_dir: PathBuf, // unexpected responses are saved here for bug reports
     pub request_counter: RequestCounter,
+    pub offline: OfflineMode,
 }
 
 impl Client {
-    pub async fn new(config: &Arc<Config>) -> Self {
-        let version = String::from(env!("CARGO_CRATE_NAME")) + " " + env!("CARGO_PKG_VERSION");
+    pub async fn new(config: &Arc<Config>) -> Result<Self, ApiError> {
+        let backend = NexusBackend::new(config.endpoints.api.clone(), http_client(&config.network)?);
+        Self::with_backend(config, Arc::new(backend)).await
+    }
 
+    pub async fn with_backend(config: &Arc<Config>, backend: Arc<dyn Backend>) -> Result<Self, ApiError> {
         let mut headers = HeaderMap::new();
-        headers.insert(USER_AGENT, HeaderValue::from_str(&version).unwrap());
+        headers.insert(USER_AGENT, user_agent(&config.network)?);
 
         let api_headers = match &config.apikey {
             Some(apikey) => {
@@ -36,44 +50,161 @@ impl Client {
             None => None,
         };
 
-        Self {
-            client: reqwest::Client::new(),
+        Ok(Self {
+            backend,
             headers: Arc::new(headers),
             api_headers: Arc::new(api_headers),
-            request_counter: RequestCounter::new(),
-        }
+            responses_dir: config.cache_for_profile().join("responses"),
+            request_counter: RequestCounter::new(config.rate_limit_reserve),
+            offline: OfflineMode::new(config.offline),
+        })
     }
 
     pub fn build_request(&self, url: Url) -> Result<reqwest::RequestBuilder, ApiError> {
-        if cfg!(test) {
-            return Err(ApiError::IsUnitTest);
-        }
-        Ok(self.client.get(url).headers((*self.headers).clone()))
+        Ok(self.backend.request(Method::GET, url)?.headers((*self.headers).clone()))
     }
 
-    fn build_api_request(&self, endpoint: &str) -> Result<reqwest::RequestBuilder, ApiError> {
-        if cfg!(test) {
-            return Err(ApiError::IsUnitTest);
-        }
-        let url: Url = Url::parse(&(String::from(API_URL) + endpoint)).unwrap();
-        let api_headers = match &*self.api_headers {
+    fn build_api_request(&self, method: Method, endpoint: &str) -> Result<reqwest::RequestBuilder, ApiError> {
+        let url: Url = self.backend.api_url().join(endpoint)?;
+        Ok(self.backend.request(method, url)?.headers(self.api_headers()?))
+    }
+
+    fn api_headers(&self) -> Result<HeaderMap, ApiError> {
+        match &*self.api_headers {
             Some(v) => Ok(v.clone()),
             None => Err(ApiError::ApiKeyMissing),
-        }?;
-
-        Ok(self.client.get(url).headers(api_headers))
+        }
     }
 
     pub async fn send_api_request(&self, endpoint: &str) -> Result<Response, ApiError> {
-        let builder = self.build_api_request(endpoint)?;
+        self.send(self.build_api_request(Method::GET, endpoint)?).await
+    }
+
+    // Servers answer with 304 Not Modified if the validators still match the requested resource
+    pub async fn send_conditional_api_request(
+        &self,
+        endpoint: &str,
+        validators: &Validators,
+    ) -> Result<Response, ApiError> {
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = validators.etag.as_ref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = validators.last_modified.as_ref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+        self.send(self.build_api_request(Method::GET, endpoint)?.headers(headers)).await
+    }
+
+    // The API expects the parameters of POST requests as form data
+    pub async fn send_api_post(&self, endpoint: &str, form: &[(&str, &str)]) -> Result<Response, ApiError> {
+        self.send(self.build_api_request(Method::POST, endpoint)?.form(form)).await
+    }
+
+    pub async fn send_api_delete(&self, endpoint: &str, form: &[(&str, &str)]) -> Result<Response, ApiError> {
+        self.send(self.build_api_request(Method::DELETE, endpoint)?.form(form)).await
+    }
+
+    // The v2 API takes GraphQL queries as JSON, and authenticates with the same API key as v1
+    pub async fn send_graphql_request(&self, url: Url, body: &Value) -> Result<Response, ApiError> {
+        self.send(self.backend.request(Method::POST, url)?.headers(self.api_headers()?).json(body)).await
+    }
+
+    /* Deserializes the body of a successful response. If the status isn't successful or the body doesn't match what's
+     * expected, the body is saved to the cache dir instead of being lost, so that changes to the Nexus API can be
+     * tracked down. */
+    pub async fn read_json<T: DeserializeOwned>(&self, endpoint: &str, resp: Response) -> Result<T, ApiError> {
+        let status = resp.status();
+        let body = resp.bytes().await?;
+        let reason = match status.is_success() {
+            true => match serde_json::from_slice(&body) {
+                Ok(value) => return Ok(value),
+                Err(e) => e.to_string(),
+            },
+            false => status.to_string(),
+        };
+
+        let text = String::from_utf8_lossy(&body);
+        let excerpt: String =
+            text.split_whitespace().collect::<Vec<&str>>().join(" ").chars().take(EXCERPT_LEN).collect();
+        // The API answers with 404 when there's nothing to find, such as an unknown md5 hash. That's not worth saving.
+        let saved_to = match status {
+            StatusCode::NOT_FOUND => None,
+            _ => self.save_response(endpoint, status, &reason, &text).await,
+        };
+        Err(ApiError::UnexpectedResponse {
+            response: Box::new(RawResponse {
+                endpoint: endpoint.to_string(),
+                status: status.as_u16(),
+                excerpt,
+                saved_to,
+            }),
+        })
+    }
+
+    async fn save_response(&self, endpoint: &str, status: StatusCode, reason: &str, body: &str) -> Option<PathBuf> {
+        let file_name: String =
+            endpoint.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '_' }).collect();
+        let path = self.responses_dir.join(format!("{}-{file_name}.txt", time::now()));
+        fs::create_dir_all(&self.responses_dir).await.ok()?;
+        let contents = format!("{endpoint}\n{status}\n{reason}\n\n{body}");
+        fs::write(&path, contents).await.ok()?;
+        Some(path)
+    }
+
+    async fn send(&self, builder: reqwest::RequestBuilder) -> Result<Response, ApiError> {
+        if self.offline.is_on() {
+            return Err(ApiError::Offline);
+        }
+        // Requests wait here while the remaining quota is below the configured reserve
+        self.request_counter.wait_for_quota().await;
         let resp = builder.send().await?;
-        /* The response headers contain a count of remaining API request quota and are tracked in api/query/queriable.rs
+        /* The response headers contain a count of remaining API request quota.
          * println!("Response headers: {:#?}\n", resp.headers());
          * println!(
          *     "Got response: {} {:?}",
          *     resp.status().as_str(),
          *     resp.status().canonical_reason()
          * ); */
+        self.request_counter.push(resp.headers()).await;
+        /* Every other request would be rejected as well, so the client goes offline for good. Only the request that
+         * rejected the key reports it, requests that were already under way fail as if they had been made offline. */
+        if resp.status() == StatusCode::UNAUTHORIZED {
+            return match self.offline.reject_apikey() {
+                true => Err(ApiError::Unauthorized),
+                false => Err(ApiError::Offline),
+            };
+        }
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = self.request_counter.set_rate_limited(resp.headers()).await;
+            return Err(ApiError::RateLimited { retry_after });
+        }
         Ok(resp)
     }
 }
+
+// API requests and file downloads share the same client, so they go through the same proxy and trust the same CAs
+fn http_client(network: &Network) -> Result<reqwest::Client, ApiError> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(network.connect_timeout)
+        .read_timeout(network.read_timeout);
+    if let Some(proxy) = &network.proxy {
+        builder = builder.proxy(Proxy::all(proxy.as_str())?);
+    }
+    for pem in &network.ca_certs {
+        for cert in Certificate::from_pem_bundle(pem)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+// The suffix is validated with the config, so this only fails for a Network that was built by hand
+pub fn user_agent(network: &Network) -> Result<HeaderValue, ApiError> {
+    let version = format!("{} {}", env!("CARGO_CRATE_NAME"), env!("CARGO_PKG_VERSION"));
+    let user_agent = match &network.user_agent_suffix {
+        Some(suffix) => format!("{version} {suffix}"),
+        None => version,
+    };
+    Ok(HeaderValue::from_str(&user_agent)?)
+}
@@ -19,7 +19,6 @@ use tokio::{task, task::JoinHandle};
 use tokio_stream::StreamExt;
 
 pub struct DownloadTask {
-    #[allow(dead_code)]
     cache: Cache,
     client: Client,
     config: Arc<Config>,
@@ -70,13 +69,25 @@ impl DownloadTask {
                 self.dl_info.set_state(DownloadState::Downloading);
                 let _ = self.start().await;
             }
-            // TODO premium users could get a new download link through the API, without having to visit Nexusmods
             DownloadState::Expired => {
-                self.dl_info.set_state(DownloadState::Expired);
-                self.logger.log(format!(
-                    "Download link for {} expired, please download again.",
-                    self.dl_info.file_info.file_name
-                ));
+                // Premium users get a new download link through the API, without having to visit Nexusmods
+                if self.cache.account.is_premium().await {
+                    let fi = &self.dl_info.file_info;
+                    match self.query.premium_download_link(&fi.game, fi.mod_id, fi.file_id).await {
+                        Ok(url) => {
+                            self.dl_info.url = url;
+                            let _ = self.start().await;
+                        }
+                        Err(e) => {
+                            self.logger.log(format!("Unable to renew download link for {}: {e}", fi.file_name));
+                        }
+                    }
+                } else {
+                    self.logger.log(format!(
+                        "Download link for {} expired, please download again.",
+                        self.dl_info.file_info.file_name
+                    ));
+                }
             }
             DownloadState::Done => return,
         }
@@ -154,6 +165,8 @@ impl DownloadTask {
         let logger = self.logger.clone();
         let file_name = file_name.clone();
         let query = self.query.clone();
+        let tracked = self.cache.tracked.clone();
+        let track_downloads = self.config.track_downloads;
         let handle: JoinHandle<()> = task::spawn(async move {
             // The actual downloading is done here
             if let Err(()) = transfer_data(file, resp, &logger, &downloads, &dl_info).await {
@@ -195,6 +208,12 @@ impl DownloadTask {
             if let Err(e) = downloads.update_metadata(&dl_info.file_info).await {
                 logger.log(format!("Unable to update metadata for downloaded file {}: {}", file_name, e));
             }
+            let (game, mod_id) = (&dl_info.file_info.game, dl_info.file_info.mod_id);
+            if track_downloads && !tracked.contains(game, mod_id).await {
+                if let Err(e) = query.track(game, mod_id).await {
+                    logger.log(format!("Unable to track mod {mod_id}: {e}"));
+                }
+            }
         });
         self.join_handle = Some(handle);
         Ok(())
@@ -300,3 +319,143 @@ async fn transfer_data(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DownloadState;
+    use crate::api::mock_server::{MockEnv, MockFile};
+    use crate::api::{DownloadInfo, FileInfo, NxmUrl};
+    use std::str::FromStr;
+    use url::Url;
+
+    async fn add_test_file(env: &MockEnv) -> MockFile {
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let file_name = "GH TR - PT Meshes-46599-1-01-1556986716.7z";
+        env.server.add_file("morrowind", 46599, 1000014318, file_name, &data)
+    }
+
+    async fn wait_for_download(env: &MockEnv, file_id: u64) {
+        let handle = env.downloads.tasks.write().await.get_mut(&file_id).unwrap().join_handle.take().unwrap();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn download_from_nxm_url() {
+        let env = MockEnv::new().await;
+        let file = add_test_file(&env).await;
+
+        env.downloads.try_queue(&env.server.nxm_url(&file)).await;
+        wait_for_download(&env, file.file_id).await;
+
+        let path = env.config.download_dir().join(&file.file_name);
+        assert_eq!(*file.data, std::fs::read(&path).unwrap());
+        assert!(!env.config.download_dir().join(format!("{}.part", file.file_name)).exists());
+        assert!(env.config.download_dir().join(format!("{}.json", file.file_name)).exists());
+        assert!(env.server.requests().iter().any(|r| r.contains("md5_search")));
+
+        let tasks = env.downloads.tasks.read().await;
+        assert!(matches!(tasks.get(&file.file_id).unwrap().dl_info.get_state(), DownloadState::Done));
+    }
+
+    #[tokio::test]
+    async fn hold_nxm_url_while_offline() {
+        let env = MockEnv::new().await;
+        let file = add_test_file(&env).await;
+
+        env.client.offline.set(true);
+        env.downloads.try_queue(&env.server.nxm_url(&file)).await;
+        assert!(env.downloads.tasks.read().await.is_empty());
+        assert!(env.server.requests().is_empty());
+
+        env.client.offline.set(false);
+        env.downloads.queue_pending().await;
+        wait_for_download(&env, file.file_id).await;
+        assert_eq!(*file.data, std::fs::read(env.config.download_dir().join(&file.file_name)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn resume_download() {
+        let env = MockEnv::new().await;
+        let file = add_test_file(&env).await;
+        let nxm = NxmUrl::from_str(&env.server.nxm_url(&file)).unwrap();
+        let url = env.query.download_link(&nxm).await.unwrap();
+
+        /* The partial file deliberately doesn't match the beginning of the archive, which lets us tell whether only
+         * the rest of the file was requested. */
+        let part_len = 40_000;
+        let part_path = env.config.download_dir().join(format!("{}.part", file.file_name));
+        std::fs::write(&part_path, vec![0; part_len]).unwrap();
+
+        let f_info = FileInfo::new(file.game.clone(), file.mod_id, file.file_id, file.file_name.clone());
+        env.downloads.add(DownloadInfo::new(f_info, url)).await;
+        wait_for_download(&env, file.file_id).await;
+
+        let contents = std::fs::read(env.config.download_dir().join(&file.file_name)).unwrap();
+        assert_eq!(file.data.len(), contents.len());
+        assert!(contents[..part_len].iter().all(|b| *b == 0));
+        assert_eq!(file.data[part_len..], contents[part_len..]);
+    }
+
+    #[tokio::test]
+    async fn renew_expired_link_for_premium() {
+        let env = MockEnv::new().await;
+        let file = add_test_file(&env).await;
+        env.server.set_premium(true);
+        env.query.validate().await.unwrap();
+
+        let expired_url = Url::parse("https://example.com/expired").unwrap();
+        let f_info = FileInfo::new(file.game.clone(), file.mod_id, file.file_id, file.file_name.clone());
+        let dl_info = DownloadInfo::new(f_info, expired_url.clone());
+        dl_info.set_state(DownloadState::Paused);
+        // The clones share the download state
+        env.downloads.add(dl_info.clone()).await;
+        dl_info.set_state(DownloadState::Expired);
+
+        env.downloads.toggle_pause_for(0).await;
+        wait_for_download(&env, file.file_id).await;
+
+        let tasks = env.downloads.tasks.read().await;
+        assert_ne!(expired_url, tasks.get(&file.file_id).unwrap().dl_info.url);
+        assert_eq!(*file.data, std::fs::read(env.config.download_dir().join(&file.file_name)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn premium_download_without_nxm_url() {
+        let env = MockEnv::new().await;
+        let file = add_test_file(&env).await;
+
+        // Free users need to visit the Nexus to get a download link
+        env.downloads.queue_premium(&file.game, file.mod_id, file.file_id).await;
+        assert!(env.downloads.tasks.read().await.is_empty());
+
+        env.server.set_premium(true);
+        env.query.validate().await.unwrap();
+        env.downloads.queue_premium(&file.game, file.mod_id, file.file_id).await;
+        wait_for_download(&env, file.file_id).await;
+        assert_eq!(*file.data, std::fs::read(env.config.download_dir().join(&file.file_name)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn queue_collection() {
+        let env = MockEnv::with_archives(&["Fair Magicka Regen v2B-39350-2-0b.rar"]).await;
+        let collection_url = "nxm://morrowind/collections/mockcollection/revisions/2";
+        let required = env.server.add_file("morrowind", 46599, 1000014601, "GH-1-04.7z", b"required file");
+        let optional = env.server.add_file("morrowind", 46599, 1000014603, "GH-1-03.7z", b"optional file");
+
+        // Free users have to download the files from the Nexus
+        env.downloads.try_queue(collection_url).await;
+        assert_eq!(3, env.cache.collection.get().await.unwrap().mod_files.len());
+        assert!(env.downloads.tasks.read().await.is_empty());
+
+        env.server.set_premium(true);
+        env.query.validate().await.unwrap();
+        env.downloads.try_queue(collection_url).await;
+        wait_for_download(&env, required.file_id).await;
+
+        // Files that are already downloaded and optional files aren't queued
+        let tasks = env.downloads.tasks.read().await;
+        assert_eq!(vec![required.file_id], tasks.keys().copied().collect::<Vec<u64>>());
+        assert!(!tasks.contains_key(&optional.file_id));
+        assert!(env.cache.archives.contains_file_id(82041).await);
+    }
+}
@@ -13,8 +13,10 @@ use crate::api::Query;
 use crate::api::{ApiError, Client, UpdateStatus};
 use crate::cache::{ArchiveEntry, ArchiveFile, ArchiveMetadata, Cache, Cacheable, ModFileMetadata};
 use crate::config::{Config, DataPath};
+use crate::extract::{ArchiveDiff, Installer, ModDirectory};
 use crate::{util, Logger};
 use indexmap::IndexMap;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::OsStr;
 use std::io::ErrorKind;
 use std::str::FromStr;
@@ -24,11 +26,27 @@ use std::sync::{
 };
 use tokio::fs;
 use tokio::sync::RwLock;
+use url::Url;
+
+// An upgrade that is installed once the user has seen the diff and accepted it
+pub struct PendingUpgrade {
+    pub dir_name: String,
+    pub archive_name: String,
+    // None if the archive couldn't be compared with the directory
+    pub diff: Option<ArchiveDiff>,
+}
 
 #[derive(Clone)]
 pub struct Downloads {
     pub tasks: Arc<RwLock<IndexMap<u64, DownloadTask>>>,
     pub has_changed: Arc<AtomicBool>,
+    pending: Arc<RwLock<Vec<String>>>, // nxm:// URLs received while offline
+    hold_pending: bool,
+    // file_id of a replacement file -> the directory it's installed into once downloaded
+    upgrades: Arc<RwLock<HashMap<u64, String>>>,
+    // Upgrades whose archive is ready, waiting for the user to accept what they change in the directory
+    pending_upgrades: Arc<RwLock<VecDeque<PendingUpgrade>>>,
+    pub installer: Installer,
     logger: Logger,
     cache: Cache,
     client: Client,
@@ -38,9 +56,15 @@ pub struct Downloads {
 
 impl Downloads {
     pub async fn new(cache: Cache, client: Client, config: Arc<Config>, logger: Logger, query: Query) -> Self {
+        let installer = Installer::new(cache.clone(), config.clone(), logger.clone()).await;
         Self {
             tasks: Arc::new(RwLock::new(IndexMap::new())),
             has_changed: Arc::new(AtomicBool::new(true)),
+            pending: Default::default(),
+            hold_pending: true,
+            upgrades: Default::default(),
+            pending_upgrades: Default::default(),
+            installer,
             cache,
             client,
             config,
@@ -49,6 +73,11 @@ impl Downloads {
         }
     }
 
+    // The daemon has no way to go back online, so nxm:// URLs it receives offline are dropped instead of held
+    pub fn drop_links_while_offline(&mut self) {
+        self.hold_pending = false;
+    }
+
     pub async fn toggle_pause_for(&self, i: usize) {
         let mut lock = self.tasks.write().await;
         let (_, task) = lock.get_index_mut(i).unwrap();
@@ -57,6 +86,21 @@ impl Downloads {
     }
 
     pub async fn try_queue(&self, nxm_str: &str) {
+        // Download links can't be requested offline, and nxm:// URLs expire, so they're held only until going online
+        if self.client.offline.is_on() {
+            // Once the API key has been rejected, going online requires a restart
+            if !self.hold_pending || self.client.offline.is_apikey_rejected() {
+                self.logger.log(format!("Offline, the download was dropped: {nxm_str}"));
+                return;
+            }
+            self.pending.write().await.push(nxm_str.to_string());
+            self.logger.log(format!("Offline, the download starts once back online: {nxm_str}"));
+            return;
+        }
+        if let Ok(collection_url) = CollectionUrl::from_str(nxm_str) {
+            self.queue_collection(collection_url).await;
+            return;
+        }
         let nxm;
         match NxmUrl::from_str(nxm_str) {
             Ok(n) => nxm = n,
@@ -71,13 +115,170 @@ impl Downloads {
             }
         }
 
+        if !self.query.is_known_game(&nxm.domain_name).await {
+            self.logger.log(format!(
+                "Unknown game \"{}\" in nxm url. Please file a bug report if the game exists on the Nexus.",
+                nxm.domain_name
+            ));
+            return;
+        }
+        if let Some(game) = self.config.game.as_ref().filter(|game| **game != nxm.domain_name) {
+            self.logger.log(format!("Warn: downloading a mod for {} to a profile for {game}.", nxm.domain_name));
+        }
+
         let url = match self.query.download_link(&nxm).await {
             Ok(url) => url,
             Err(_e) => return,
         };
+        self.queue(nxm.domain_name, nxm.mod_id, nxm.file_id, url).await;
+    }
+
+    // Queues the nxm:// URLs that were received while offline
+    pub async fn queue_pending(&self) {
+        let pending: Vec<String> = self.pending.write().await.drain(..).collect();
+        for nxm_str in pending {
+            self.try_queue(&nxm_str).await;
+        }
+    }
+
+    // Premium users can download files without an nxm:// URL from the Nexus
+    pub async fn queue_premium(&self, game: &str, mod_id: u32, file_id: u64) {
+        if !self.cache.account.is_premium().await {
+            self.logger.log("Downloading without visiting the Nexus requires a premium account.");
+            return;
+        }
+        if let Ok(url) = self.query.premium_download_link(game, mod_id, file_id).await {
+            self.queue(game.to_string(), mod_id, file_id, url).await;
+        }
+    }
+
+    /* Upgrades an out of date installed mod to the newest file in its update chain. The replacement is installed into
+     * the same directory once it has been downloaded. Premium users get it downloaded directly, others need to download
+     * it from the Nexus page that is returned. */
+    pub async fn upgrade(&self, dir_name: &str) -> Option<String> {
+        let Some((dir_name, ModDirectory::Nexus(im))) = self.cache.installed.get(dir_name).await else {
+            return None;
+        };
+        let mfd = self.cache.metadata_index.get_by_file_id(&im.file_id).await?;
+        if !matches!(mfd.update_status.to_enum(), UpdateStatus::OutOfDate(_)) {
+            self.logger.log(format!("{dir_name} is not out of date."));
+            return None;
+        }
+        let Some(replacement) = mfd.replacement().await else {
+            self.logger.log(format!("No replacement for {dir_name} was found in its update chain."));
+            return None;
+        };
+
+        if let Some(ArchiveEntry::File(_)) = self.cache.archives.get(&replacement.file_name).await {
+            self.install_upgrade(dir_name, replacement.file_name).await;
+            return None;
+        }
+        self.upgrades.write().await.insert(replacement.file_id, dir_name.clone());
+        if self.cache.account.is_premium().await {
+            self.queue_premium(&im.game, im.mod_id, replacement.file_id).await;
+            None
+        } else {
+            self.logger.log(format!("Download {} from the Nexus to upgrade {dir_name}.", replacement.file_name));
+            Some(format!(
+                "https://www.nexusmods.com/{}/mods/{}?tab=files&file_id={}",
+                im.game, im.mod_id, replacement.file_id
+            ))
+        }
+    }
+
+    /* The files the upgrade changes are compared first, in case any of them were edited since the mod was installed.
+     * The upgrade waits for the UI to ask the user, see next_pending_upgrade(). */
+    async fn install_upgrade(&self, dir_name: String, archive_name: String) {
+        let diff = match self.installer.diff(&archive_name, &dir_name).await {
+            Ok(diff) => {
+                self.logger.log(format!("Comparing {archive_name} with {dir_name}: {diff}"));
+                for line in diff.lines() {
+                    self.logger.log(line);
+                }
+                Some(diff)
+            }
+            Err(e) => {
+                self.logger.log(format!("Unable to compare {archive_name} with {dir_name}: {e}"));
+                None
+            }
+        };
+        self.pending_upgrades.write().await.push_back(PendingUpgrade {
+            dir_name,
+            archive_name,
+            diff,
+        });
+    }
+
+    pub async fn next_pending_upgrade(&self) -> Option<PendingUpgrade> {
+        self.pending_upgrades.write().await.pop_front()
+    }
+
+    // Installs the upgrade if the user accepted its diff
+    pub async fn finish_upgrade(&self, upgrade: PendingUpgrade, accepted: bool) {
+        let PendingUpgrade {
+            dir_name, archive_name, ..
+        } = upgrade;
+        if !accepted {
+            self.logger.log(format!("Cancelled upgrading {dir_name} to {archive_name}."));
+            return;
+        }
+        self.logger.log(format!("Upgrading {dir_name} to {archive_name}"));
+        if let Err(e) = self.installer.upgrade(dir_name.clone(), archive_name).await {
+            self.logger.log(format!("Unable to upgrade {dir_name}: {e}"));
+        }
+    }
+
+    /* Looks up the files of a collection revision. Premium users get the files that aren't downloaded yet queued
+     * automatically, except for optional ones. Others need to visit the page of each file, which the Collection tab
+     * lists. */
+    pub async fn queue_collection(&self, url: CollectionUrl) {
+        let revision = match self.query.collection_revision(&url.game, &url.slug, url.revision).await {
+            Ok(revision) => revision,
+            Err(e) => {
+                self.logger.log(format!("Unable to look up collection {} revision {}: {e}", url.slug, url.revision));
+                return;
+            }
+        };
+
+        let mut missing = vec![];
+        for mf in &revision.mod_files {
+            if !self.cache.archives.contains_file_id(mf.file_id).await
+                && !self.tasks.read().await.contains_key(&mf.file_id)
+            {
+                missing.push(mf);
+            }
+        }
+        self.logger.log(format!(
+            "Collection {} revision {}: {} of {} files are missing.",
+            revision.collection.name,
+            revision.revision_number,
+            missing.len(),
+            revision.mod_files.len()
+        ));
+        if missing.is_empty() {
+            return;
+        }
+
+        if self.cache.account.is_premium().await {
+            let (optional, required): (Vec<_>, Vec<_>) = missing.into_iter().partition(|mf| mf.optional);
+            if !optional.is_empty() {
+                self.logger.log(format!(
+                    "{} optional files weren't queued, the Collection tab lists them for downloading separately.",
+                    optional.len()
+                ));
+            }
+            for mf in required {
+                self.queue_premium(mf.game(), mf.mod_id(), mf.file_id).await;
+            }
+        } else {
+            self.logger.log("The Collection tab lists the files to download from the Nexus.");
+        }
+    }
+
+    async fn queue(&self, game: String, mod_id: u32, file_id: u64, url: Url) {
         let file_name = util::file_name_from_url(&url);
 
-        if let Some(task) = self.tasks.write().await.get_mut(&nxm.file_id) {
+        if let Some(task) = self.tasks.write().await.get_mut(&file_id) {
             match task.dl_info.get_state() {
                 DownloadState::Downloading => {
                     self.logger.log(format!("Download of {} is already in progress.", file_name));
@@ -105,7 +306,7 @@ impl Downloads {
                 }
             }
         } // an else {} branch wouldn't drop the lock here, causing self.add() to deadlock
-        let f_info = FileInfo::new(nxm.domain_name, nxm.mod_id, nxm.file_id, file_name);
+        let f_info = FileInfo::new(game, mod_id, file_id, file_name);
         self.add(DownloadInfo::new(f_info, url)).await;
     }
 
@@ -140,7 +341,12 @@ impl Downloads {
              * time is the timestamp of the newest file in the mod. */
             let latest_timestamp = file_list.files.last().unwrap().uploaded_timestamp;
 
-            if file_list.file_updates.binary_search_by(|upd| fi.file_id.cmp(&upd.old_file_id)).is_ok() {
+            let replacement = file_list.newest_replacement(fi.file_id);
+            let is_replaced = replacement.is_some();
+            if let Some(mfd) = self.cache.metadata_index.get_by_file_id(&fi.file_id).await {
+                mfd.set_replacement(replacement).await;
+            }
+            if is_replaced {
                 return UpdateStatus::OutOfDate(latest_timestamp);
             }
             if let Some(filedata_heap) = self.cache.metadata_index.get_modfiles(&fi.game, &fi.mod_id).await {
@@ -204,8 +410,13 @@ impl Downloads {
             if let Err(e) = archive_json.save(DataPath::ArchiveMetadata(&self.config, &archive.file_name)).await {
                 self.logger.log(format!("Unable to save metadata for {}: {e}", &fi.file_name));
             }
+            let archive_name = archive.file_name.clone();
             let entry = ArchiveEntry::File(Arc::new(archive));
             self.cache.archives.add_archive(entry.clone()).await;
+            let upgrade = self.upgrades.write().await.remove(&fi.file_id);
+            if let Some(dir_name) = upgrade {
+                self.install_upgrade(dir_name, archive_name).await;
+            }
         } else {
             self.logger.log(format!("Error: recently downloaded file {} is inaccessible..?", &fi.file_name));
         }
@@ -264,3 +475,81 @@ impl Downloads {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::mock_server::MockEnv;
+    use crate::api::Replacement;
+    use crate::extract::{InstalledMod, ModDirectory, ModRepository};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn upgrade_from_nexus_page() {
+        let env = MockEnv::with_archives(&["Graphic Herbalism MWSE - OpenMW-46599-1-03-1556986083.7z"]).await;
+        let file_id = 1000014314;
+        let mfd = env.cache.metadata_index.get_by_file_id(&file_id).await.unwrap();
+        mfd.set_replacement(Some(Replacement {
+            file_id: 1000014601,
+            file_name: "Graphic Herbalism MWSE - OpenMW-46599-1-04-1558643353.7z".to_string(),
+            version: Some("1.04".to_string()),
+        }))
+        .await;
+        env.cache.installed.mods.write().await.insert(
+            "Graphic Herbalism".to_string(),
+            ModDirectory::Nexus(Arc::new(InstalledMod {
+                game: "morrowind".to_string(),
+                mod_id: 46599,
+                file_id,
+                name: None,
+                mod_name: None,
+                version: Some("1.03".to_string()),
+                category_id: None,
+                category_name: None,
+                installation_file: "Graphic Herbalism MWSE - OpenMW-46599-1-03-1556986083.7z".to_string(),
+                repository: ModRepository::Nexus,
+                last_update_check: Default::default(),
+                update_status: mfd.update_status.clone(),
+                installed_timestamp: 0,
+            })),
+        );
+
+        // Accounts that aren't premium download the replacement from its page on the Nexus
+        let url = env.downloads.upgrade("Graphic Herbalism").await;
+        assert_eq!(Some("https://www.nexusmods.com/morrowind/mods/46599?tab=files&file_id=1000014601"), url.as_deref());
+        let upgrades = env.downloads.upgrades.read().await;
+        assert_eq!(Some("Graphic Herbalism"), upgrades.get(&1000014601).map(String::as_str));
+    }
+
+    #[tokio::test]
+    async fn upgrade_waits_for_confirmation() {
+        let archive = "GH TR - PT Meshes-46599-1-01-1556986716.7z";
+        let env = MockEnv::with_archives(&[archive]).await;
+        let dir = env.config.install_dir().join("GH TR");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("user.cfg"), b"created by the user").unwrap();
+
+        env.downloads.install_upgrade("GH TR".to_string(), archive.to_string()).await;
+        let upgrade = env.downloads.next_pending_upgrade().await.unwrap();
+        assert_eq!(vec![PathBuf::from("user.cfg")], upgrade.diff.as_ref().unwrap().removed);
+        assert!(env.downloads.next_pending_upgrade().await.is_none());
+
+        // The directory is left alone when the diff isn't accepted
+        env.downloads.finish_upgrade(upgrade, false).await;
+        assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+    }
+
+    #[tokio::test]
+    async fn daemon_drops_links_offline() {
+        let mut env = MockEnv::new().await;
+        env.client.offline.set(true);
+        let nxm = "nxm://morrowind/mods/46599/files/1000014601?key=abc&expires=9999999999&user_id=1234";
+        env.downloads.try_queue(nxm).await;
+        assert_eq!(1, env.downloads.pending.read().await.len());
+
+        env.downloads.drop_links_while_offline();
+        env.downloads.pending.write().await.clear();
+        env.downloads.try_queue(nxm).await;
+        assert!(env.downloads.pending.read().await.is_empty());
+    }
+}
@@ -58,6 +58,33 @@ impl FromStr for NxmUrl {
     }
 }
 
+// Clicking "Download" on a collection's page opens a link to one of its revisions
+#[derive(Debug)]
+pub struct CollectionUrl {
+    pub game: String,
+    pub slug: String,
+    pub revision: u32,
+}
+
+impl FromStr for CollectionUrl {
+    type Err = ApiError;
+
+    // Example: nxm://skyrimspecialedition/collections/qdurkx/revisions/42
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(s)?;
+        let game = url.host_str().ok_or(ApiError::InvalidNxmUrl)?.to_string();
+        let segments: Vec<&str> = url.path_segments().ok_or(ApiError::InvalidNxmUrl)?.collect();
+        match segments.as_slice() {
+            ["collections", slug, "revisions", revision] => Ok(CollectionUrl {
+                game: check_game_special_case(game),
+                slug: slug.to_string(),
+                revision: revision.parse()?,
+            }),
+            _ => Err(ApiError::InvalidNxmUrl),
+        }
+    }
+}
+
 /* The nxm link protocol isn't synced with the API protocol for all games. At least these two are
  * special cases, but there might be more.
  */
@@ -80,7 +107,7 @@ fn check_expiration(expires: &u64) -> Result<(), ApiError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::api::{ApiError, NxmUrl};
+    use crate::api::{ApiError, CollectionUrl, NxmUrl};
     use std::str::FromStr;
 
     #[test]
@@ -92,4 +119,17 @@ mod tests {
         }
         panic!("Nxm link should have expired");
     }
+
+    #[test]
+    fn collection_url() -> Result<(), ApiError> {
+        let url = CollectionUrl::from_str("nxm://SkyrimSE/collections/qdurkx/revisions/42")?;
+        assert_eq!("skyrimspecialedition", url.game);
+        assert_eq!("qdurkx", url.slug);
+        assert_eq!(42, url.revision);
+
+        let nxm_str =
+            "nxm://SkyrimSE/mods/8850/files/27772?key=XnbXtdAspojLzUAn7x-Grw&expires=1583065790&user_id=1234321";
+        assert!(matches!(CollectionUrl::from_str(nxm_str), Err(ApiError::InvalidNxmUrl)));
+        Ok(())
+    }
 }
@@ -0,0 +1,59 @@
+use super::ApiError;
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+type SharedRequest<T> = Shared<BoxFuture<'static, Result<Arc<T>, Arc<ApiError>>>>;
+
+/* Requests that are currently being sent, keyed by what they request. Identical requests made at the same time share
+ * the response of the first one, so they only use up one request of the quota and write the cache once. */
+pub struct InFlight<T> {
+    requests: Arc<Mutex<HashMap<String, SharedRequest<T>>>>,
+}
+
+impl<T> Clone for InFlight<T> {
+    fn clone(&self) -> Self {
+        Self {
+            requests: self.requests.clone(),
+        }
+    }
+}
+
+impl<T> Default for InFlight<T> {
+    fn default() -> Self {
+        Self {
+            requests: Default::default(),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> InFlight<T> {
+    pub async fn coalesce<F>(&self, key: String, request: F) -> Result<Arc<T>, ApiError>
+    where
+        F: Future<Output = Result<Arc<T>, ApiError>> + Send + 'static,
+    {
+        let shared = {
+            let mut requests = self.requests.lock().unwrap();
+            match requests.get(&key) {
+                Some(shared) => shared.clone(),
+                None => {
+                    let map = self.requests.clone();
+                    let k = key.clone();
+                    let shared = async move {
+                        let res = request.await.map_err(Arc::new);
+                        // Requests made after this one has finished are sent again
+                        map.lock().unwrap().remove(&k);
+                        res
+                    }
+                    .boxed()
+                    .shared();
+                    requests.insert(key, shared.clone());
+                    shared
+                }
+            }
+        };
+        // Every waiter holds a reference to the error, so the last one to finish gets the original
+        shared.await.map_err(|e| Arc::try_unwrap(e).unwrap_or_else(|e| e.duplicate()))
+    }
+}
@@ -0,0 +1,694 @@
+/* A local stand-in for the Nexus, used by tests that need to exercise the network code.
+ *
+ * The server speaks just enough HTTP/1.1 for reqwest. API responses are served from the fixtures in test/data, while
+ * the archives themselves are registered by the tests with add_file(). Download links point back to this server,
+ * which supports Range requests for resuming downloads. The rate limit headers are tracked like the Nexus does, and
+ * requests are answered with 429 Too Many Requests once both quotas are used up.
+ *
+ * MockEnv sets up the rest of the program with temporary download and data directories, so that running tests doesn't
+ * write into the fixtures. */
+
+use super::backend::Backend;
+use super::{ApiError, Client, Downloads, Query, UpdateChecker};
+use crate::cache::Cache;
+use crate::config::{Config, ConfigBuilder};
+use crate::util::time;
+use crate::Logger;
+use md5::{Digest, Md5};
+use reqwest::{Method, RequestBuilder};
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task;
+use url::Url;
+
+const HOURLY_LIMIT: u16 = 100;
+const DAILY_LIMIT: u16 = 2500;
+
+#[derive(Clone)]
+pub struct MockFile {
+    pub game: String,
+    pub mod_id: u32,
+    pub file_id: u64,
+    pub file_name: String,
+    pub data: Arc<Vec<u8>>,
+}
+
+impl MockFile {
+    pub fn md5(&self) -> String {
+        format!("{:x}", Md5::digest(self.data.as_slice()))
+    }
+}
+
+struct State {
+    fixtures: PathBuf,
+    files: Mutex<HashMap<u64, MockFile>>,
+    hourly_remaining: Mutex<u16>,
+    daily_remaining: Mutex<u16>,
+    endorsements: Mutex<HashMap<(String, u32), &'static str>>,
+    mod_statuses: Mutex<HashMap<(String, u32), &'static str>>,
+    tracked: Mutex<BTreeSet<(String, u32)>>,
+    is_premium: Mutex<bool>,
+    apikey_revoked: Mutex<bool>,
+    requests: Mutex<Vec<String>>,
+}
+
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(State {
+            fixtures: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data").join(env!("CARGO_CRATE_NAME")),
+            files: Mutex::new(HashMap::new()),
+            hourly_remaining: Mutex::new(HOURLY_LIMIT),
+            daily_remaining: Mutex::new(DAILY_LIMIT),
+            endorsements: Mutex::new(HashMap::new()),
+            mod_statuses: Mutex::new(HashMap::new()),
+            tracked: Mutex::new(BTreeSet::new()),
+            is_premium: Mutex::new(false),
+            apikey_revoked: Mutex::new(false),
+            requests: Mutex::new(vec![]),
+        });
+
+        let st = state.clone();
+        task::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let st = st.clone();
+                task::spawn(async move {
+                    let _ = handle_connection(stream, addr, &st).await;
+                });
+            }
+        });
+        Self { addr, state }
+    }
+
+    pub fn backend(&self) -> Arc<dyn Backend> {
+        Arc::new(MockBackend {
+            client: reqwest::Client::new(),
+            api_url: Url::parse(&format!("http://{}/v1/", self.addr)).unwrap(),
+        })
+    }
+
+    pub fn add_file(&self, game: &str, mod_id: u32, file_id: u64, file_name: &str, data: &[u8]) -> MockFile {
+        let file = MockFile {
+            game: game.to_string(),
+            mod_id,
+            file_id,
+            file_name: file_name.to_string(),
+            data: Arc::new(data.to_vec()),
+        };
+        self.state.files.lock().unwrap().insert(file_id, file.clone());
+        file
+    }
+
+    pub fn set_remaining(&self, hourly: u16, daily: u16) {
+        *self.state.hourly_remaining.lock().unwrap() = hourly;
+        *self.state.daily_remaining.lock().unwrap() = daily;
+    }
+
+    pub fn set_premium(&self, is_premium: bool) {
+        *self.state.is_premium.lock().unwrap() = is_premium;
+    }
+
+    // Every API request is answered with 401 Unauthorized afterwards
+    pub fn revoke_apikey(&self) {
+        *self.state.apikey_revoked.lock().unwrap() = true;
+    }
+
+    pub fn endorse_status(&self, game: &str, mod_id: u32) -> &'static str {
+        let endorsements = self.state.endorsements.lock().unwrap();
+        endorsements.get(&(game.to_string(), mod_id)).copied().unwrap_or("Undecided")
+    }
+
+    // Mods that aren't "published" are also marked unavailable, like hidden and removed mods on the Nexus
+    pub fn set_mod_status(&self, game: &str, mod_id: u32, status: &'static str) {
+        self.state.mod_statuses.lock().unwrap().insert((game.to_string(), mod_id), status);
+    }
+
+    pub fn track(&self, game: &str, mod_id: u32) {
+        self.state.tracked.lock().unwrap().insert((game.to_string(), mod_id));
+    }
+
+    pub fn is_tracked(&self, game: &str, mod_id: u32) -> bool {
+        self.state.tracked.lock().unwrap().contains(&(game.to_string(), mod_id))
+    }
+
+    // Paths of the requests received so far, without the query string
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    pub fn nxm_url(&self, file: &MockFile) -> String {
+        format!(
+            "nxm://{}/mods/{}/files/{}?key=mockkey&expires={}&user_id=1234",
+            file.game,
+            file.mod_id,
+            file.file_id,
+            time::now() + 3600
+        )
+    }
+}
+
+struct MockBackend {
+    client: reqwest::Client,
+    api_url: Url,
+}
+
+impl Backend for MockBackend {
+    fn api_url(&self) -> &Url {
+        &self.api_url
+    }
+
+    fn request(&self, method: Method, url: Url) -> Result<RequestBuilder, ApiError> {
+        Ok(self.client.request(method, url))
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    range_start: Option<u64>,
+    if_none_match: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    // Form data is url-encoded just like the query string
+    fn form_param(&self, name: &str) -> Option<String> {
+        url::form_urlencoded::parse(&self.body).find(|(k, _)| k == name).map(|(_, v)| v.to_string())
+    }
+}
+
+struct Response {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body,
+        }
+    }
+
+    fn json(value: Value) -> Self {
+        let mut resp = Self::new("200 OK", value.to_string().into_bytes());
+        resp.headers.push(("Content-Type", "application/json".to_string()));
+        resp
+    }
+
+    fn not_found() -> Self {
+        Self::new("404 Not Found", b"{\"message\":\"Not Found\"}".to_vec())
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, addr: SocketAddr, state: &State) -> std::io::Result<()> {
+    let mut buf = vec![];
+    let mut chunk = [0; 4096];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let head_len = buf.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+    let head = String::from_utf8_lossy(&buf[..head_len]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or("GET").to_string();
+    let target = request_line.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = percent_encoding::percent_decode_str(path).decode_utf8_lossy().to_string();
+    let query = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+
+    let mut range_start = None;
+    let mut if_none_match = None;
+    let mut content_length = 0;
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("range") {
+                range_start = value.trim().strip_prefix("bytes=").and_then(|r| r.trim_end_matches('-').parse().ok());
+            } else if name.eq_ignore_ascii_case("if-none-match") {
+                if_none_match = Some(value.trim().to_string());
+            } else if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or_default();
+            }
+        }
+    }
+    while buf.len() < head_len + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    state.requests.lock().unwrap().push(path.clone());
+
+    let req = Request {
+        method,
+        path,
+        query,
+        range_start,
+        if_none_match,
+        body: buf.split_off(head_len),
+    };
+    let resp = if let Some(endpoint) = req.path.strip_prefix("/v1/") {
+        api_response(state, addr, &req, endpoint)
+    } else if req.path == "/search/mods" {
+        search(state, &req)
+    } else if req.path == "/graphql" {
+        graphql(state, &req)
+    } else {
+        file_response(state, &req)
+    };
+
+    let mut out = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", resp.status, resp.body.len());
+    for (name, value) in resp.headers {
+        out.push_str(&format!("{name}: {value}\r\n"));
+    }
+    out.push_str("\r\n");
+    stream.write_all(out.as_bytes()).await?;
+    stream.write_all(&resp.body).await?;
+    stream.shutdown().await
+}
+
+fn api_response(state: &State, addr: SocketAddr, req: &Request, endpoint: &str) -> Response {
+    if *state.apikey_revoked.lock().unwrap() {
+        return Response::new("401 Unauthorized", b"{\"message\":\"Please provide a valid API Key\"}".to_vec());
+    }
+    let (hourly, daily) = {
+        let mut hourly = state.hourly_remaining.lock().unwrap();
+        let mut daily = state.daily_remaining.lock().unwrap();
+        if *hourly == 0 && *daily == 0 {
+            let mut resp = Response::new("429 Too Many Requests", b"{\"msg\":\"Rate limited\"}".to_vec());
+            resp.headers.push(("Retry-After", "3600".to_string()));
+            return resp;
+        }
+        // Like on the Nexus, the daily quota is used up first
+        match *daily > 0 {
+            true => *daily -= 1,
+            false => *hourly -= 1,
+        }
+        (*hourly, *daily)
+    };
+
+    let segments: Vec<&str> = endpoint.split('/').collect();
+    let mut resp = match segments.as_slice() {
+        ["games.json"] => games(state),
+        ["games", game] => {
+            fixture(state.fixtures.join(format!("metadata/{}/game_info.json", game.trim_end_matches(".json"))))
+        }
+        ["games", game, "mods", "updated.json"] => updated(state, game),
+        ["games", game, "mods", "latest_added.json"] => latest_mods(state, game, "created_timestamp"),
+        ["games", game, "mods", "latest_updated.json" | "trending.json"] => {
+            latest_mods(state, game, "updated_timestamp")
+        }
+        ["games", game, "mods", "md5_search", md5] => md5_search(state, game, md5.trim_end_matches(".json")),
+        ["games", game, "mods", mod_id, "files.json"] => {
+            fixture(state.fixtures.join(format!("profiles/testprofile/metadata/{game}/file_lists/{mod_id}.json")))
+        }
+        ["games", _game, "mods", _mod_id, "files", file_id, "download_link.json"] => {
+            download_link(state, addr, file_id)
+        }
+        ["games", game, "mods", mod_id, "changelogs.json"] => {
+            // Mods without changelogs get an empty array
+            let path = state.fixtures.join(format!("metadata/{game}/changelogs/{mod_id}.json"));
+            Response::json(read_fixture(path).unwrap_or(json!([])))
+        }
+        ["games", game, "mods", mod_id, "endorse.json"] => set_endorsement(state, game, mod_id, "Endorsed"),
+        ["games", game, "mods", mod_id, "abstain.json"] => set_endorsement(state, game, mod_id, "Abstained"),
+        ["games", game, "mods", mod_id] => mod_info(state, game, mod_id.trim_end_matches(".json")),
+        ["user", "tracked_mods.json"] => tracked_mods(state, req),
+        ["users", "validate.json"] => validate(state),
+        _ => Response::not_found(),
+    };
+    // Successful responses are tagged with a hash of their body, and not sent again while it matches
+    if resp.status == "200 OK" {
+        let etag = format!("\"{:x}\"", Md5::digest(&resp.body));
+        if req.if_none_match.as_ref() == Some(&etag) {
+            resp = Response::new("304 Not Modified", vec![]);
+        }
+        resp.headers.push(("ETag", etag));
+    }
+
+    let now = time::now();
+    resp.headers.push(("x-rl-hourly-limit", HOURLY_LIMIT.to_string()));
+    resp.headers.push(("x-rl-hourly-remaining", hourly.to_string()));
+    resp.headers.push(("x-rl-hourly-reset", format_timestamp(now - now % 3600 + 3600, "T", "+00:00")));
+    resp.headers.push(("x-rl-daily-limit", DAILY_LIMIT.to_string()));
+    resp.headers.push(("x-rl-daily-remaining", daily.to_string()));
+    resp.headers.push(("x-rl-daily-reset", format_timestamp(now - now % 86400 + 86400, " ", " +0000")));
+    resp
+}
+
+fn fixture(path: PathBuf) -> Response {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Response::json(serde_json::from_str(&contents).unwrap()),
+        Err(_) => Response::not_found(),
+    }
+}
+
+// The fixtures lack the user specific endorsement status, which is added here along with any changed mod status
+fn mod_info(state: &State, game: &str, mod_id: &str) -> Response {
+    let Some(mut mod_info) = read_fixture(state.fixtures.join(format!("metadata/{game}/mod_info/{mod_id}.json")))
+    else {
+        return Response::not_found();
+    };
+    let key = mod_id.parse().ok().map(|id: u32| (game.to_string(), id));
+    let endorsements = state.endorsements.lock().unwrap();
+    let status = key.as_ref().and_then(|key| endorsements.get(key).copied());
+    mod_info["endorsement"] =
+        json!({ "endorse_status": status.unwrap_or("Undecided"), "timestamp": null, "version": null });
+    if let Some(mod_status) = key.and_then(|key| state.mod_statuses.lock().unwrap().get(&key).copied()) {
+        mod_info["status"] = json!(mod_status);
+        mod_info["available"] = json!(mod_status == "published");
+    }
+    Response::json(mod_info)
+}
+
+// Every mod with a mod info fixture, newest first
+fn latest_mods(state: &State, game: &str, sort_by: &str) -> Response {
+    let dir = state.fixtures.join(format!("metadata/{game}/mod_info"));
+    let entries = std::fs::read_dir(dir).into_iter().flatten().flatten();
+    let mut mods: Vec<Value> = entries.filter_map(|entry| read_fixture(entry.path())).collect();
+    mods.sort_by_key(|mi| std::cmp::Reverse(mi[sort_by].as_u64()));
+    Response::json(Value::Array(mods))
+}
+
+fn set_endorsement(state: &State, game: &str, mod_id: &str, status: &'static str) -> Response {
+    let Ok(mod_id) = mod_id.parse() else {
+        return Response::not_found();
+    };
+    state.endorsements.lock().unwrap().insert((game.to_string(), mod_id), status);
+    Response::json(json!({ "message": "Updated endorse status", "status": status }))
+}
+
+// Every game with a game info fixture
+fn games(state: &State) -> Response {
+    let Ok(dirs) = std::fs::read_dir(state.fixtures.join("metadata")) else {
+        return Response::json(json!([]));
+    };
+    let games = dirs.flatten().filter_map(|dir| read_fixture(dir.path().join("game_info.json"))).collect();
+    Response::json(Value::Array(games))
+}
+
+// Matches the terms against the names of the mods that have a mod info fixture
+fn search(state: &State, req: &Request) -> Response {
+    let terms: Vec<String> =
+        req.query_param("terms").unwrap_or_default().split(',').map(|t| t.to_lowercase()).collect();
+    let game_id = req.query_param("game_id").and_then(|id| id.parse::<u64>().ok());
+    let games = std::fs::read_dir(state.fixtures.join("metadata")).into_iter().flatten().flatten();
+    let game = games.map(|dir| dir.path()).find(|dir| {
+        game_id.is_some() && read_fixture(dir.join("game_info.json")).and_then(|gi| gi["id"].as_u64()) == game_id
+    });
+
+    let mut results = vec![];
+    let mod_infos = game.and_then(|dir| std::fs::read_dir(dir.join("mod_info")).ok()).into_iter().flatten().flatten();
+    for mi in mod_infos.filter_map(|entry| read_fixture(entry.path())) {
+        let name = mi["name"].as_str().unwrap_or_default().to_string();
+        if !terms.iter().all(|t| name.to_lowercase().contains(t)) {
+            continue;
+        }
+        results.push(json!({
+            "name": name,
+            "downloads": mi["mod_downloads"].as_u64().unwrap_or_default(),
+            "endorsements": mi["endorsement_count"].as_u64().unwrap_or_default(),
+            "url": format!("/{}/mods/{}", mi["domain_name"].as_str().unwrap_or_default(), mi["mod_id"]),
+            "image": mi["picture_url"],
+            "username": mi["uploaded_by"],
+            "user_id": mi["user"]["member_id"],
+            "game_id": mi["game_id"],
+            "mod_id": mi["mod_id"],
+        }));
+    }
+    Response::json(json!({
+        "terms": terms,
+        "exclude_authors": [],
+        "exclude_tags": [],
+        "include_adult": false,
+        "took": 1,
+        "total": results.len(),
+        "results": results,
+    }))
+}
+
+// Only the collection revision query is supported, answered from the collection fixtures
+fn graphql(state: &State, req: &Request) -> Response {
+    if *state.apikey_revoked.lock().unwrap() {
+        return Response::new("401 Unauthorized", b"{\"message\":\"Please provide a valid API Key\"}".to_vec());
+    }
+    let body: Value = serde_json::from_slice(&req.body).unwrap_or_default();
+    let variables = &body["variables"];
+    let (game, slug) = (variables["domainName"].as_str().unwrap_or_default(), variables["slug"].as_str());
+    let path = state.fixtures.join(format!("metadata/{game}/collections/{}.json", slug.unwrap_or_default()));
+    match read_fixture(path) {
+        Some(revision) => Response::json(json!({ "data": { "collectionRevision": revision } })),
+        None => Response::json(json!({ "data": null, "errors": [{ "message": "Collection not found" }] })),
+    }
+}
+
+fn validate(state: &State) -> Response {
+    let is_premium = *state.is_premium.lock().unwrap();
+    Response::json(json!({
+        "user_id": 1234,
+        "key": "1234",
+        "name": "mockuser",
+        "is_premium?": is_premium,
+        "is_supporter?": is_premium,
+        "email": "mockuser@example.com",
+        "profile_url": "https://www.nexusmods.com/users/1234",
+        "is_premium": is_premium,
+        "is_supporter": is_premium
+    }))
+}
+
+fn tracked_mods(state: &State, req: &Request) -> Response {
+    let mut tracked = state.tracked.lock().unwrap();
+    if req.method == "GET" {
+        let mods: Vec<Value> = tracked.iter().map(|(game, id)| json!({ "mod_id": id, "domain_name": game })).collect();
+        return Response::json(Value::Array(mods));
+    }
+    let game = req.query_param("domain_name").unwrap_or_default().to_string();
+    let Some(mod_id) = req.form_param("mod_id").and_then(|id| id.parse().ok()) else {
+        return Response::not_found();
+    };
+    match req.method.as_str() {
+        "POST" => {
+            tracked.insert((game, mod_id));
+            Response::json(json!({ "message": format!("User 1234 is now Tracking Mod: {mod_id}") }))
+        }
+        "DELETE" => {
+            tracked.remove(&(game, mod_id));
+            Response::json(json!({ "message": format!("User 1234 is no longer tracking {mod_id}") }))
+        }
+        _ => Response::not_found(),
+    }
+}
+
+fn read_fixture(path: PathBuf) -> Option<Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn download_link(state: &State, addr: SocketAddr, file_id: &str) -> Response {
+    let files = state.files.lock().unwrap();
+    match file_id.parse().ok().and_then(|id: u64| files.get(&id)) {
+        Some(file) => {
+            let mut url = Url::parse(&format!("http://{addr}/files/")).unwrap();
+            url.path_segments_mut().unwrap().pop_if_empty().push(&file.file_name);
+            Response::json(json!([{ "name": "Mock", "short_name": "Mock", "URI": url.as_str() }]))
+        }
+        None => Response::not_found(),
+    }
+}
+
+// Results are built from the mod info and file list fixtures, with the md5 of the registered file
+fn md5_search(state: &State, game: &str, md5: &str) -> Response {
+    let files = state.files.lock().unwrap();
+    let mut results = vec![];
+    for file in files.values().filter(|f| f.game == game && f.md5() == md5) {
+        let mod_info = read_fixture(state.fixtures.join(format!("metadata/{game}/mod_info/{}.json", file.mod_id)));
+        let file_list = read_fixture(
+            state.fixtures.join(format!("profiles/testprofile/metadata/{game}/file_lists/{}.json", file.mod_id)),
+        );
+        let file_details = file_list.as_ref().and_then(|fl| {
+            fl["files"].as_array()?.iter().find(|fd| fd["file_id"].as_u64() == Some(file.file_id)).cloned()
+        });
+        if let (Some(mod_info), Some(mut file_details)) = (mod_info, file_details) {
+            file_details["md5"] = json!(md5);
+            results.push(json!({ "mod": mod_info, "file_details": file_details }));
+        }
+    }
+    match results.is_empty() {
+        true => Response::not_found(),
+        false => Response::json(Value::Array(results)),
+    }
+}
+
+// Every mod with a file list fixture counts as recently updated
+fn updated(state: &State, game: &str) -> Response {
+    let dir = state.fixtures.join(format!("profiles/testprofile/metadata/{game}/file_lists"));
+    let mut updates = vec![];
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let Some(fl) = read_fixture(entry.path()) else {
+                continue;
+            };
+            let mod_id: u32 = entry.path().file_stem().unwrap().to_string_lossy().parse().unwrap();
+            let latest = fl["files"].as_array().into_iter().flatten().filter_map(|f| f["uploaded_timestamp"].as_u64());
+            let latest = latest.max().unwrap_or_default();
+            updates.push(json!({ "mod_id": mod_id, "latest_file_update": latest, "latest_mod_activity": latest }));
+        }
+    }
+    updates.sort_by_key(|u| u["mod_id"].as_u64());
+    Response::json(Value::Array(updates))
+}
+
+fn file_response(state: &State, req: &Request) -> Response {
+    let Some(file_name) = req.path.strip_prefix("/files/") else {
+        return Response::not_found();
+    };
+    let files = state.files.lock().unwrap();
+    let Some(file) = files.values().find(|f| f.file_name == file_name) else {
+        return Response::not_found();
+    };
+    match req.range_start {
+        Some(start) if start < file.data.len() as u64 => {
+            let mut resp = Response::new("206 Partial Content", file.data[start as usize..].to_vec());
+            resp.headers
+                .push(("Content-Range", format!("bytes {}-{}/{}", start, file.data.len() - 1, file.data.len())));
+            resp
+        }
+        Some(_) => Response::new("416 Range Not Satisfiable", vec![]),
+        None => Response::new("200 OK", file.data.to_vec()),
+    }
+}
+
+// The inverse of time::parse_timestamp(), for the reset headers
+fn format_timestamp(secs: u64, separator: &str, offset: &str) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    // Howard Hinnant's civil_from_days
+    let z = days + 719468;
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}{separator}{:02}:{:02}:{:02}{offset}",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+pub struct MockEnv {
+    pub server: MockServer,
+    pub config: Arc<Config>,
+    pub logger: Logger,
+    pub cache: Cache,
+    pub client: Client,
+    pub query: Query,
+    pub downloads: Downloads,
+    dir: PathBuf,
+}
+
+impl MockEnv {
+    pub async fn new() -> Self {
+        Self::with_archives(&[]).await
+    }
+
+    // Copies the given archives from test/downloads, along with their metadata, before the cache is loaded
+    pub async fn with_archives(archives: &[&str]) -> Self {
+        let dir = std::env::temp_dir().join(format!("{}-test-{}", env!("CARGO_CRATE_NAME"), uuid::Uuid::new_v4()));
+        let server = MockServer::start().await;
+        let config = ConfigBuilder::default()
+            .profile("testprofile")
+            .apikey("1234")
+            .data_dir(dir.join("data").to_string_lossy())
+            .download_dir(dir.join("downloads").to_string_lossy())
+            .install_dir(dir.join("install").to_string_lossy())
+            .search_endpoint(format!("http://{}/search/mods", server.addr))
+            .graphql_endpoint(format!("http://{}/graphql", server.addr))
+            .build()
+            .unwrap();
+        let config = Arc::new(config);
+
+        std::fs::create_dir_all(config.download_dir()).unwrap();
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/downloads/dmodman/testprofile");
+        for archive in archives {
+            std::fs::copy(fixtures.join(archive), config.download_dir().join(archive)).unwrap();
+            let json = format!("{archive}.json");
+            std::fs::copy(fixtures.join(&json), config.download_dir().join(&json)).unwrap();
+        }
+
+        let logger = Logger::default();
+        let cache = Cache::new(config.clone(), logger.clone()).await.unwrap();
+        let client = Client::with_backend(&config, server.backend()).await.unwrap();
+        let query = Query::new(cache.clone(), client.clone(), config.clone(), logger.clone());
+        let downloads =
+            Downloads::new(cache.clone(), client.clone(), config.clone(), logger.clone(), query.clone()).await;
+        Self {
+            server,
+            config,
+            logger,
+            cache,
+            client,
+            query,
+            downloads,
+            dir,
+        }
+    }
+
+    // An update checker that shares the cache and client of the environment
+    pub fn updater(&self) -> UpdateChecker {
+        UpdateChecker::new(
+            self.cache.clone(),
+            self.client.clone(),
+            self.config.clone(),
+            self.logger.clone(),
+            self.query.clone(),
+        )
+    }
+}
+
+impl Drop for MockEnv {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_timestamp;
+    use crate::util::time;
+
+    #[test]
+    fn format_reset_timestamps() {
+        let t = 1716940800;
+        assert_eq!("2024-05-29T00:00:00+00:00", format_timestamp(t, "T", "+00:00"));
+        assert_eq!("2024-05-29 00:00:00 +0000", format_timestamp(t, " ", " +0000"));
+        assert_eq!(Some(t + 3723), time::parse_timestamp(&format_timestamp(t + 3723, "T", "+00:00")));
+    }
+}
@@ -1,18 +1,26 @@
 mod api_error;
+pub mod backend;
 mod client;
 pub mod downloads;
+mod in_flight;
+#[cfg(test)]
+pub mod mock_server;
 pub mod nexus_api;
+mod offline_mode;
 mod query;
 mod request_counter;
 pub mod sso;
 pub mod update_checker;
+mod update_progress;
 pub mod update_status;
 
 pub use api_error::*;
 pub use client::*;
 pub use downloads::*;
 pub use nexus_api::*;
+pub use offline_mode::{OfflineMode, OFFLINE_MSG};
 pub use query::*;
 pub use request_counter::RequestCounter;
 pub use update_checker::*;
+pub use update_progress::UpdateProgress;
 pub use update_status::*;
@@ -0,0 +1,68 @@
+use crate::api::Queriable;
+use crate::cache::Cacheable;
+use crate::util::version;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+/* The changelog entries of a mod, keyed by mod version. The entries can contain HTML.
+ * Example: {"1.0":["Initial release"],"1.1":["Fixed a crash<br />Added a config menu"]} */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Changelogs {
+    #[serde(deserialize_with = "versions")]
+    pub versions: HashMap<String, Vec<String>>,
+    // When the changelogs were fetched, set by the cache
+    #[serde(skip)]
+    pub fetched: u64,
+}
+
+impl Changelogs {
+    // The entries for versions newer than the given one, newest first. All entries are returned if there's no version.
+    pub fn newer_than(&self, version: Option<&str>) -> Vec<(&str, &[String])> {
+        let mut newer: Vec<(&str, &[String])> = self
+            .versions
+            .iter()
+            .filter(|(v, _)| version.is_none_or(|version| version::compare(v, version).is_gt()))
+            .map(|(v, entries)| (v.as_str(), entries.as_slice()))
+            .collect();
+        newer.sort_by(|(a, _), (b, _)| version::compare(b, a));
+        newer
+    }
+}
+
+// Mods without changelogs get an empty array instead of an object
+fn versions<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Versions {
+        Map(HashMap<String, Vec<String>>),
+        Empty(serde::de::IgnoredAny),
+    }
+    Ok(match Versions::deserialize(deserializer)? {
+        Versions::Map(map) => map,
+        Versions::Empty(_) => HashMap::new(),
+    })
+}
+
+impl Cacheable for Changelogs {}
+
+impl Queriable for Changelogs {
+    const FORMAT_STRING: &'static str = "games/{}/mods/{}/changelogs.json";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Changelogs;
+
+    #[test]
+    fn deserialize_changelogs() {
+        let json = r#"{"1.02":["Fixed a crash"],"1.10":["Added a menu","Fixed typos"],"1.9":["Cleanup"]}"#;
+        let changelogs: Changelogs = serde_json::from_str(json).unwrap();
+        let newer: Vec<&str> = changelogs.newer_than(Some("1.2")).into_iter().map(|(v, _)| v).collect();
+        assert_eq!(vec!["1.10", "1.9"], newer);
+        assert_eq!(3, changelogs.newer_than(None).len());
+
+        let empty: Changelogs = serde_json::from_str("[]").unwrap();
+        assert!(empty.versions.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::api::ApiError;
+use crate::cache::Cacheable;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/* Collections are only available through the v2 API, which is queried with GraphQL. Only the fields needed to download
+ * the files of a revision are requested. */
+pub const COLLECTION_REVISION_QUERY: &str = "
+query CollectionRevision($slug: String!, $revision: Int, $domainName: String) {
+  collectionRevision(slug: $slug, revision: $revision, domainName: $domainName, viewAdultContent: true) {
+    revisionNumber
+    collection { name slug }
+    modFiles {
+      fileId
+      optional
+      file { name version mod { modId name game { domainName } } }
+    }
+  }
+}";
+
+#[derive(Deserialize)]
+pub struct GraphQlResponse<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<GraphQlError>,
+}
+
+#[derive(Deserialize)]
+pub struct GraphQlError {
+    pub message: String,
+}
+
+impl<T: DeserializeOwned> GraphQlResponse<T> {
+    // GraphQL errors are reported in the response body instead of the status code
+    pub fn into_result(self) -> Result<T, ApiError> {
+        match (self.data, self.errors.into_iter().next()) {
+            (Some(data), None) => Ok(data),
+            (_, Some(e)) => Err(ApiError::GraphQlError { message: e.message }),
+            (None, None) => Err(ApiError::GraphQlError {
+                message: "response has no data".to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRevisionData {
+    pub collection_revision: CollectionRevision,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRevision {
+    pub revision_number: u32,
+    pub collection: CollectionInfo,
+    pub mod_files: Vec<CollectionModFile>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CollectionInfo {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionModFile {
+    pub file_id: u64,
+    pub optional: bool,
+    pub file: CollectionFile,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CollectionFile {
+    pub name: String,
+    pub version: Option<String>,
+    #[serde(rename = "mod")]
+    pub mod_info: CollectionMod,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionMod {
+    pub mod_id: u32,
+    pub name: String,
+    pub game: CollectionGame,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionGame {
+    pub domain_name: String,
+}
+
+impl Cacheable for CollectionRevision {}
+
+impl CollectionModFile {
+    pub fn game(&self) -> &str {
+        &self.file.mod_info.game.domain_name
+    }
+
+    pub fn mod_id(&self) -> u32 {
+        self.file.mod_info.mod_id
+    }
+}
@@ -0,0 +1,14 @@
+use super::EndorseStatus;
+use serde::Deserialize;
+
+/* Response to both endorsing and abstaining. These are POST requests, so they aren't Queriable.
+ * Example: {"message":"Updated endorse status","status":"Endorsed"} */
+#[derive(Debug, Deserialize)]
+pub struct EndorseResponse {
+    #[allow(dead_code)]
+    pub message: Option<String>,
+    pub status: EndorseStatus,
+}
+
+pub const ENDORSE_FORMAT_STRING: &str = "games/{}/mods/{}/endorse.json";
+pub const ABSTAIN_FORMAT_STRING: &str = "games/{}/mods/{}/abstain.json";
@@ -2,6 +2,7 @@ use crate::api::Queriable;
 use crate::cache::Cacheable;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -50,6 +51,68 @@ pub struct FileDetails {
     pub changelog_html: Option<String>,
 }
 
+// The newest file that replaces an old one, found by following the update chain of the old file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replacement {
+    pub file_id: u64,
+    pub file_name: String,
+    pub version: Option<String>,
+}
+
+impl FileList {
+    /* Follows the old_file_id -> new_file_id links in file_updates until reaching a file that hasn't been replaced.
+     * If a file has been replaced more than once, the most recent update is followed. */
+    pub fn newest_replacement(&self, file_id: u64) -> Option<Replacement> {
+        let mut newest_updates: HashMap<u64, &FileUpdate> = HashMap::new();
+        for upd in &self.file_updates {
+            let newest = newest_updates.entry(upd.old_file_id).or_insert(upd);
+            if *newest < upd {
+                *newest = upd;
+            }
+        }
+
+        let mut visited = HashSet::from([file_id]);
+        let mut newest = None;
+        let mut current = file_id;
+        while let Some(upd) = newest_updates.get(&current) {
+            // Guard against the chain looping back on itself
+            if !visited.insert(upd.new_file_id) {
+                break;
+            }
+            newest = Some(*upd);
+            current = upd.new_file_id;
+        }
+
+        let upd = newest?;
+        let version = match self.files.binary_search_by(|fd| fd.file_id.cmp(&upd.new_file_id)) {
+            Ok(index) => self.files[index].version.clone(),
+            Err(_) => None,
+        };
+        Some(Replacement {
+            file_id: upd.new_file_id,
+            file_name: upd.new_file_name.clone(),
+            version,
+        })
+    }
+
+    // Whether the old file leads to the new one through any of the links in file_updates
+    pub fn is_replaced_by(&self, old_file_id: u64, new_file_id: u64) -> bool {
+        let mut visited = HashSet::from([old_file_id]);
+        let mut to_visit = vec![old_file_id];
+        while let Some(file_id) = to_visit.pop() {
+            for upd in self.file_updates.iter().filter(|upd| upd.old_file_id == file_id) {
+                if upd.new_file_id == new_file_id {
+                    return true;
+                }
+                if visited.insert(upd.new_file_id) {
+                    to_visit.push(upd.new_file_id);
+                }
+            }
+        }
+        false
+    }
+}
+
 impl Cacheable for FileList {}
 impl Queriable for FileList {
     const FORMAT_STRING: &'static str = "games/{}/mods/{}/files.json";
@@ -97,3 +160,32 @@ impl PartialOrd for FileUpdate {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FileList;
+    use crate::api::ApiError;
+    use crate::cache::Cacheable;
+    use crate::config::{ConfigBuilder, DataPath};
+
+    #[tokio::test]
+    async fn follow_update_chain() -> Result<(), ApiError> {
+        let config = ConfigBuilder::default().profile("testprofile").build().unwrap();
+        let fl = FileList::load(DataPath::FileList(&config, "morrowind", 46599)).await?;
+
+        // 1000014198 -> 1000014225 -> 1000014314 -> 1000014601
+        let replacement = fl.newest_replacement(1000014198).unwrap();
+        assert_eq!(1000014601, replacement.file_id);
+        assert_eq!("Graphic Herbalism MWSE - OpenMW-46599-1-04-1558643353.7z", replacement.file_name);
+        assert_eq!(Some("1.04"), replacement.version.as_deref());
+
+        // 1000014226 was replaced twice, the later update wins
+        assert_eq!(1000014317, fl.newest_replacement(1000014226).unwrap().file_id);
+
+        assert!(fl.newest_replacement(1000014601).is_none());
+
+        assert!(fl.is_replaced_by(1000014198, 1000014314));
+        assert!(!fl.is_replaced_by(1000014601, 1000014198));
+        Ok(())
+    }
+}
@@ -1,31 +1,90 @@
-use serde::{Deserialize, Serialize};
+use crate::api::Queriable;
+use crate::cache::Cacheable;
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Serialize, Deserialize)]
+// Every game on the Nexus. The list is large and changes rarely, so it's cached and refreshed only once in a while.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Games {
-    games: Vec<GameInfo>,
+    pub games: Vec<GameInfo>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct GameInfo {
-    id: u64,
-    name: String,
-    forum_url: String,
-    nexusmods_url: String,
-    genre: String,
-    file_count: u64,
-    downloads: u64,
-    domain_name: String,
-    approved_date: u64,
-    file_views: u64,
-    authors: u64,
-    file_endorsements: u64,
-    mods: u64,
-    categories: Vec<Category>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct Category {
-    category_id: u64,
-    name: String,
-    parent_category: bool,
+    pub id: u64,
+    pub name: String,
+    pub forum_url: String,
+    pub nexusmods_url: String,
+    pub genre: String,
+    pub file_count: u64,
+    pub downloads: u64,
+    pub domain_name: String,
+    pub approved_date: u64,
+    pub file_views: u64,
+    pub authors: u64,
+    pub file_endorsements: u64,
+    pub mods: u64,
+    pub categories: Vec<Category>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Category {
+    pub category_id: u32,
+    pub name: String,
+    // The API uses false instead of an id for top level categories
+    #[allow(dead_code)]
+    #[serde(default, deserialize_with = "parent_category")]
+    pub parent_category: Option<u32>,
+}
+
+impl GameInfo {
+    pub fn category_name(&self, category_id: u32) -> Option<&str> {
+        self.categories.iter().find(|c| c.category_id == category_id).map(|c| c.name.as_str())
+    }
+}
+
+fn parent_category<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Parent {
+        Id(u32),
+        Other(serde::de::IgnoredAny),
+    }
+    Ok(match Parent::deserialize(deserializer)? {
+        Parent::Id(id) => Some(id),
+        Parent::Other(_) => None,
+    })
+}
+
+impl Cacheable for Games {}
+impl Cacheable for GameInfo {}
+
+impl Queriable for Games {
+    const FORMAT_STRING: &'static str = "games.json";
+}
+
+impl Queriable for GameInfo {
+    const FORMAT_STRING: &'static str = "games/{}.json";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GameInfo;
+    use crate::cache::Cacheable;
+    use crate::config::{ConfigBuilder, DataPath};
+    use std::error::Error;
+
+    #[tokio::test]
+    async fn deserialize_game_info() -> Result<(), Box<dyn Error>> {
+        let config = ConfigBuilder::default().build().unwrap();
+        let game_info = GameInfo::load(DataPath::GameInfo(&config, "morrowind")).await?;
+        assert_eq!("Morrowind", game_info.name);
+
+        let buildings = game_info.categories.iter().find(|c| c.category_id == 2).unwrap();
+        assert_eq!(Some(1), buildings.parent_category);
+        assert_eq!(None, game_info.categories.first().unwrap().parent_category);
+        assert_eq!(Some("Buildings"), game_info.category_name(2));
+        Ok(())
+    }
 }
@@ -0,0 +1,52 @@
+use crate::api::{ModInfo, Queriable};
+use serde::Deserialize;
+use std::fmt;
+
+/* The lists of mods shown on the front page of a game on the Nexus. Each contains the full mod info of ten or so
+ * mods. */
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct LatestAdded {
+    pub mods: Vec<ModInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct LatestUpdated {
+    pub mods: Vec<ModInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct Trending {
+    pub mods: Vec<ModInfo>,
+}
+
+impl Queriable for LatestAdded {
+    const FORMAT_STRING: &'static str = "games/{}/mods/latest_added.json";
+}
+
+impl Queriable for LatestUpdated {
+    const FORMAT_STRING: &'static str = "games/{}/mods/latest_updated.json";
+}
+
+impl Queriable for Trending {
+    const FORMAT_STRING: &'static str = "games/{}/mods/trending.json";
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ModList {
+    LatestAdded,
+    LatestUpdated,
+    Trending,
+}
+
+impl fmt::Display for ModList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModList::LatestAdded => f.write_str("Latest added"),
+            ModList::LatestUpdated => f.write_str("Latest updated"),
+            ModList::Trending => f.write_str("Trending"),
+        }
+    }
+}
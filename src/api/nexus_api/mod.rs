@@ -1,16 +1,27 @@
+mod changelogs;
+mod collection_revision;
 mod download_link;
+mod endorse;
 mod file_list;
 mod games;
+mod latest_mods;
 mod md5_search;
 mod mod_info;
 mod search;
+mod tracked_mods;
 mod updated;
+mod validate;
 
+pub use self::changelogs::*;
+pub use self::collection_revision::*;
 pub use self::download_link::*;
+pub use self::endorse::*;
 pub use self::file_list::*;
-#[allow(unused_imports)]
-pub use self::games::*; // unused endpoint
+pub use self::games::*;
+pub use self::latest_mods::*;
 pub use self::md5_search::*;
 pub use self::mod_info::*;
 pub use self::search::*;
+pub use self::tracked_mods::*;
 pub use self::updated::*;
+pub use self::validate::*;
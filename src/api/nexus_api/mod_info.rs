@@ -33,18 +33,26 @@ pub struct ModInfo {
     #[allow(dead_code)]
     #[serde(skip)]
     pub user: Option<UserInfo>,
-    #[allow(dead_code)]
-    #[serde(skip)]
+    // Only present when the request was made with an API key. Older cached responses don't have it.
+    #[serde(default)]
     pub endorsement: Option<Endorsement>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Endorsement {
-    pub endorse_status: String,
-    pub timestamp: Option<u32>,
+    pub endorse_status: EndorseStatus,
+    pub timestamp: Option<u64>,
     pub version: Option<String>,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EndorseStatus {
+    Endorsed,
+    Abstained,
+    #[serde(other)]
+    Undecided,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UserInfo {
     pub member_group_id: u32,
@@ -52,6 +60,14 @@ pub struct UserInfo {
     pub name: String,
 }
 
+impl ModInfo {
+    /* Mods can be hidden by their author, or removed or put under moderation by the Nexus. Their files can't be
+     * downloaded until the mod is published again, so the local archives might be the only copies left. */
+    pub fn is_unavailable(&self) -> bool {
+        !self.available || self.status != "published"
+    }
+}
+
 impl Cacheable for ModInfo {}
 
 impl Queriable for ModInfo {
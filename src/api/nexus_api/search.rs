@@ -3,8 +3,6 @@ use crate::api::Query;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
 
-// This is not used, but it should work in case it's needed
-
 #[derive(Serialize, Deserialize)]
 pub struct Search {
     pub terms: Vec<String>,
@@ -16,7 +14,7 @@ pub struct Search {
     pub results: Vec<SearchResult>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub name: String,
     pub downloads: u64,
@@ -38,7 +36,6 @@ pub struct SearchQuery {
     pub include_adult: bool,
 }
 
-#[allow(dead_code)]
 impl SearchQuery {
     pub async fn send(&self, query: Query) -> Result<Search, ApiError> {
         query.mod_search(self.format()).await
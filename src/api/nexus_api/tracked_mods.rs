@@ -0,0 +1,26 @@
+use crate::api::Queriable;
+use crate::cache::Cacheable;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct TrackedMods {
+    pub mods: Vec<TrackedMod>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct TrackedMod {
+    pub domain_name: String,
+    pub mod_id: u32,
+}
+
+impl Cacheable for TrackedMods {}
+
+impl Queriable for TrackedMods {
+    // All mods tracked by the user, across every game
+    const FORMAT_STRING: &'static str = "user/tracked_mods.json";
+}
+
+/* Tracking and untracking are POST and DELETE requests to the same endpoint, with the mod id as form data.
+ * Example response: {"message":"User 1234 is now Tracking Mod: 46599"} */
+pub const TRACK_FORMAT_STRING: &str = "user/tracked_mods.json?domain_name={}";
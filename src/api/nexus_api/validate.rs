@@ -0,0 +1,23 @@
+use crate::api::Queriable;
+use crate::cache::Cacheable;
+use serde::{Deserialize, Serialize};
+
+/* The account the API key belongs to. The response also contains the API key itself, which is left out so it isn't
+ * written to the cache.
+ * Example: {"user_id":1234,"key":"...","name":"user","is_premium":false,"is_supporter":false,"email":"...",
+ * "profile_url":"https://..."} */
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserDetails {
+    pub user_id: u64,
+    pub name: String,
+    pub is_premium: bool,
+    pub is_supporter: bool,
+    #[allow(dead_code)]
+    pub profile_url: Option<String>,
+}
+
+impl Cacheable for UserDetails {}
+
+impl Queriable for UserDetails {
+    const FORMAT_STRING: &'static str = "users/validate.json";
+}
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub const OFFLINE_MSG: &str = "Offline mode: answering from cache. nxm:// links are held until going online.";
+
+/* Whether API requests are allowed. While offline, Query and UpdateChecker only answer from the cache, and requests that
+ * reach the Client fail with ApiError::Offline without touching the network. */
+#[derive(Clone)]
+pub struct OfflineMode {
+    offline: Arc<AtomicBool>,
+    apikey_rejected: Arc<AtomicBool>,
+    pub has_changed: Arc<AtomicBool>,
+}
+
+impl OfflineMode {
+    pub fn new(offline: bool) -> Self {
+        Self {
+            offline: Arc::new(AtomicBool::new(offline)),
+            apikey_rejected: Arc::new(AtomicBool::new(false)),
+            has_changed: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    // Returns false if the mode was already set to the given value, or if going online isn't possible
+    pub fn set(&self, offline: bool) -> bool {
+        if !offline && self.is_apikey_rejected() {
+            return false;
+        }
+        let changed = self.offline.swap(offline, Ordering::Relaxed) != offline;
+        if changed {
+            self.has_changed.store(true, Ordering::Relaxed);
+        }
+        changed
+    }
+
+    /* Every request with an API key that the Nexus has rejected would fail the same way, so the key is dropped and
+     * going online is refused until dmodman is restarted. Returns false if the key was already rejected. */
+    pub fn reject_apikey(&self) -> bool {
+        self.set(true);
+        !self.apikey_rejected.swap(true, Ordering::Relaxed)
+    }
+
+    pub fn is_apikey_rejected(&self) -> bool {
+        self.apikey_rejected.load(Ordering::Relaxed)
+    }
+}
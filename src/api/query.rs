@@ -1,22 +1,33 @@
 pub use super::downloads::nxm_url::*;
+use super::in_flight::InFlight;
 use super::nexus_api::*;
 use crate::api::ApiError;
-use crate::cache::ModFileMetadata;
+use crate::cache::{Cacheable, ModFileMetadata, Validators};
+use crate::config::DataPath;
+use crate::extract::ModDirectory;
 use crate::util;
+use crate::util::time;
 use crate::{Cache, Client, Config, Logger};
+use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::fs;
 use url::Url;
 
-const SEARCH_URL: &str = "https://search.nexusmods.com/mods";
+// An unknown game triggers a refresh of the list of games, unless it's newer than this
+const RECHECK_GAMES_AFTER_SECS: u64 = 60 * 60;
 
 #[derive(Clone)]
 pub struct Query {
     cache: Cache,
     client: Client,
-    #[allow(dead_code)]
     config: Arc<Config>,
     logger: Logger,
+    file_lists_in_flight: InFlight<FileList>,
+    mod_info_in_flight: InFlight<ModInfo>,
 }
 
 impl Query {
@@ -26,6 +37,8 @@ impl Query {
             client,
             config,
             logger,
+            file_lists_in_flight: Default::default(),
+            mod_info_in_flight: Default::default(),
         }
     }
 
@@ -39,20 +52,24 @@ impl Query {
     }
 
     pub async fn download_link(&self, nxm: &NxmUrl) -> Result<Url, ApiError> {
+        self.request_download_link(&nxm.domain_name, nxm.mod_id, nxm.file_id, &nxm.query).await
+    }
+
+    // Premium users can generate download links without the key and expiry time of an nxm:// URL
+    pub async fn premium_download_link(&self, game: &str, mod_id: u32, file_id: u64) -> Result<Url, ApiError> {
+        self.request_download_link(game, mod_id, file_id, "").await
+    }
+
+    async fn request_download_link(&self, game: &str, mod_id: u32, file_id: u64, query: &str) -> Result<Url, ApiError> {
         match DownloadLink::request(
             &self.client,
             // TODO get rid of passing an array as argument
-            &[
-                &nxm.domain_name,
-                &nxm.mod_id.to_string(),
-                &nxm.file_id.to_string(),
-                &nxm.query,
-            ],
+            &[game, &mod_id.to_string(), &file_id.to_string(), query],
         )
         .await
         {
             Ok(dl_links) => {
-                self.cache.save_download_links(&dl_links, &nxm.domain_name, nxm.mod_id, nxm.file_id).await?;
+                self.cache.save_download_links(&dl_links, game, mod_id, file_id).await?;
                 /* The API returns multiple locations for Premium users. The first option is by default the Premium-only
                  * global CDN, unless the user has selected a preferred download location.
                  * For small files the download URL is the same regardless of location choice.
@@ -77,15 +94,272 @@ impl Query {
         }
     }
 
+    // Checks which account the API key belongs to. Fails with ApiError::Unauthorized if the key isn't valid.
+    pub async fn validate(&self) -> Result<UserDetails, ApiError> {
+        let details = UserDetails::request(&self.client, &[]).await?;
+        self.cache.account.set(details.clone()).await;
+        Ok(details)
+    }
+
+    // Mod info of a mod that's already being requested is shared with the pending request
     pub async fn mod_info(&self, game: &str, mod_id: u32) -> Result<Arc<ModInfo>, ApiError> {
-        let mod_info = Arc::new(ModInfo::request(&self.client, &[game, &mod_id.to_string()]).await?);
-        self.cache.save_modinfo(mod_info.clone()).await;
-        Ok(mod_info)
+        let query = self.clone();
+        let game = game.to_string();
+        let key = format!("{game}/{mod_id}");
+        self.mod_info_in_flight
+            .coalesce(key, async move { query.request_mod_info(&game, mod_id).await })
+            .await
+    }
+
+    async fn request_mod_info(&self, game: &str, mod_id: u32) -> Result<Arc<ModInfo>, ApiError> {
+        let cached = self.cache.mod_info.get(game, mod_id).await;
+        if self.client.offline.is_on() {
+            return cached.ok_or(ApiError::Offline);
+        }
+        let validators = match cached {
+            Some(_) => ModInfo::load_validators(DataPath::ModInfo(&self.config, game, mod_id)).await,
+            None => Validators::default(),
+        };
+        match ModInfo::request_if_modified(&self.client, &[game, &mod_id.to_string()], &validators).await? {
+            Conditional::Modified(mod_info, validators) => {
+                let mod_info = Arc::new(mod_info);
+                self.cache.save_modinfo(mod_info.clone()).await;
+                self.save_validators::<ModInfo>(DataPath::ModInfo(&self.config, game, mod_id), &validators).await;
+                Ok(mod_info)
+            }
+            Conditional::NotModified => Ok(cached.unwrap()),
+        }
     }
 
+    /* Fetches one of the lists of new and popular mods of a game. Mods that are already known keep their cached mod
+     * info unless the list has a newer one, which is saved in place of the old. Mod info of other mods is only kept in
+     * memory, so browsing doesn't fill the cache with mods that are never downloaded. */
+    pub async fn mod_list(&self, game: &str, list: ModList) -> Result<Vec<Arc<ModInfo>>, ApiError> {
+        let mods = match list {
+            ModList::LatestAdded => LatestAdded::request(&self.client, &[game]).await?.mods,
+            ModList::LatestUpdated => LatestUpdated::request(&self.client, &[game]).await?.mods,
+            ModList::Trending => Trending::request(&self.client, &[game]).await?.mods,
+        };
+        let mut ret = vec![];
+        for mut mod_info in mods {
+            let mod_info = match self.cache.mod_info.get(game, mod_info.mod_id).await {
+                Some(known) if known.updated_timestamp >= mod_info.updated_timestamp => known,
+                Some(known) => {
+                    if mod_info.endorsement.is_none() {
+                        mod_info.endorsement = known.endorsement.clone();
+                    }
+                    let mod_info = Arc::new(mod_info);
+                    self.cache.save_modinfo(mod_info.clone()).await;
+                    mod_info
+                }
+                None => {
+                    let mod_info = Arc::new(mod_info);
+                    self.cache.mod_info.insert(mod_info.clone()).await;
+                    mod_info
+                }
+            };
+            ret.push(mod_info);
+        }
+        Ok(ret)
+    }
+
+    // Like mod info, a file list that's already being requested is shared with the pending request
     pub async fn file_list(&self, game: &str, mod_id: u32) -> Result<Arc<FileList>, ApiError> {
-        let file_list = FileList::request(&self.client, &[game, &mod_id.to_string()]).await?;
-        Ok(self.cache.save_file_list(file_list, game, mod_id).await)
+        let query = self.clone();
+        let game = game.to_string();
+        let key = format!("{game}/{mod_id}");
+        self.file_lists_in_flight
+            .coalesce(key, async move { query.request_file_list(&game, mod_id).await })
+            .await
+    }
+
+    /* File lists of big mods are large, so the cached file list is revalidated instead of downloaded again when it's
+     * still valid. */
+    async fn request_file_list(&self, game: &str, mod_id: u32) -> Result<Arc<FileList>, ApiError> {
+        let cached = self.cache.file_lists.get(game, mod_id).await;
+        if self.client.offline.is_on() {
+            return cached.ok_or(ApiError::Offline);
+        }
+        let validators = match cached {
+            Some(_) => FileList::load_validators(DataPath::FileList(&self.config, game, mod_id)).await,
+            None => Validators::default(),
+        };
+        match FileList::request_if_modified(&self.client, &[game, &mod_id.to_string()], &validators).await? {
+            Conditional::Modified(file_list, validators) => {
+                let file_list = self.cache.save_file_list(file_list, game, mod_id).await;
+                self.save_validators::<FileList>(DataPath::FileList(&self.config, game, mod_id), &validators).await;
+                Ok(file_list)
+            }
+            Conditional::NotModified => Ok(cached.unwrap()),
+        }
+    }
+
+    async fn save_validators<T: Cacheable>(&self, path: DataPath<'_>, validators: &Validators) {
+        if let Err(e) = T::save_validators(path, validators).await {
+            self.logger.log(format!("Unable to save the ETag and Last-Modified of a response: {e}"));
+        }
+    }
+
+    pub async fn changelogs(&self, game: &str, mod_id: u32) -> Result<Arc<Changelogs>, ApiError> {
+        let mut changelogs = Changelogs::request(&self.client, &[game, &mod_id.to_string()]).await?;
+        changelogs.fetched = time::now();
+        Ok(self.cache.changelogs.insert(game, mod_id, changelogs).await)
+    }
+
+    pub async fn endorse(&self, game: &str, mod_id: u32) -> Result<EndorseStatus, ApiError> {
+        self.set_endorsement(ENDORSE_FORMAT_STRING, game, mod_id).await
+    }
+
+    pub async fn abstain(&self, game: &str, mod_id: u32) -> Result<EndorseStatus, ApiError> {
+        self.set_endorsement(ABSTAIN_FORMAT_STRING, game, mod_id).await
+    }
+
+    // Abstains from endorsing mods that are already endorsed, and endorses the rest
+    pub async fn toggle_endorsement(&self, game: &str, mod_id: u32) -> Result<EndorseStatus, ApiError> {
+        match self.endorse_status(game, mod_id).await {
+            Some(EndorseStatus::Endorsed) => self.abstain(game, mod_id).await,
+            _ => self.endorse(game, mod_id).await,
+        }
+    }
+
+    pub async fn endorse_status(&self, game: &str, mod_id: u32) -> Option<EndorseStatus> {
+        let mod_info = self.cache.mod_info.get(game, mod_id).await?;
+        mod_info.endorsement.as_ref().map(|e| e.endorse_status)
+    }
+
+    async fn set_endorsement(&self, format_string: &str, game: &str, mod_id: u32) -> Result<EndorseStatus, ApiError> {
+        let mod_info = match self.cache.mod_info.get(game, mod_id).await {
+            Some(mi) => mi,
+            None => self.mod_info(game, mod_id).await?,
+        };
+        let endpoint = util::format::vec_with_format_string(format_string, &[game, &mod_id.to_string()]);
+        let version = mod_info.version.clone().unwrap_or_default();
+        let resp = self.client.send_api_post(&endpoint, &[("version", &version)]).await?.error_for_status()?;
+        let status = resp.json::<EndorseResponse>().await?.status;
+
+        let mut mod_info = (*mod_info).clone();
+        mod_info.endorsement = Some(Endorsement {
+            endorse_status: status,
+            timestamp: Some(time::now()),
+            version: mod_info.version.clone(),
+        });
+        self.cache.save_modinfo(Arc::new(mod_info)).await;
+        Ok(status)
+    }
+
+    /* The mods that have been installed for at least min_age seconds, except for the ones that are known to be endorsed
+     * or abstained from already. */
+    pub async fn installed_to_endorse(&self, min_age: u64) -> HashSet<(String, u32)> {
+        let now = time::now();
+        let mut to_check: HashSet<(String, u32)> = HashSet::new();
+        for (dir_name, mod_dir) in self.cache.installed.mods.read().await.iter() {
+            if let ModDirectory::Nexus(im) = mod_dir {
+                let installed_at = match im.installed_timestamp {
+                    0 => self.dir_modified(dir_name).await.unwrap_or(now),
+                    t => t,
+                };
+                if now.saturating_sub(installed_at) >= min_age {
+                    to_check.insert((im.game.clone(), im.mod_id));
+                }
+            }
+        }
+        let mut to_endorse = HashSet::new();
+        for (game, mod_id) in to_check {
+            if matches!(self.endorse_status(&game, mod_id).await, None | Some(EndorseStatus::Undecided)) {
+                to_endorse.insert((game, mod_id));
+            }
+        }
+        to_endorse
+    }
+
+    /* Endorses the given mods, see installed_to_endorse(). Mods that the user has abstained from endorsing are left
+     * alone. Returns the amount of mods endorsed. */
+    pub async fn endorse_installed(&self, to_check: HashSet<(String, u32)>) -> usize {
+        let mut endorsed = 0;
+        for (game, mod_id) in to_check {
+            // Cached mod info from older versions doesn't know the endorsement status
+            let status = match self.endorse_status(&game, mod_id).await {
+                Some(status) => Some(status),
+                None => match self.mod_info(&game, mod_id).await {
+                    Ok(mi) => mi.endorsement.as_ref().map(|e| e.endorse_status),
+                    Err(e) => {
+                        self.logger.log(format!("Unable to check endorsement status of mod {mod_id}: {e}"));
+                        continue;
+                    }
+                },
+            };
+            if let Some(EndorseStatus::Undecided) = status {
+                match self.endorse(&game, mod_id).await {
+                    Ok(_) => endorsed += 1,
+                    Err(e) => self.logger.log(format!("Failed to endorse mod {mod_id}: {e}")),
+                }
+            }
+        }
+        endorsed
+    }
+
+    async fn dir_modified(&self, dir_name: &str) -> Option<u64> {
+        let modified = fs::metadata(self.config.install_dir().join(dir_name)).await.ok()?.modified().ok()?;
+        modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+    }
+
+    pub async fn games(&self) -> Result<(), ApiError> {
+        let games = Games::request(&self.client, &[]).await?;
+        self.cache.games.replace(games).await;
+        Ok(())
+    }
+
+    // Fetches the list of games unless it's newer than max_age seconds
+    pub async fn refresh_games(&self, max_age: u64) -> Result<(), ApiError> {
+        match self.cache.games.age().await {
+            Some(age) if age < max_age => Ok(()),
+            _ => self.games().await,
+        }
+    }
+
+    /* Checks whether the game domain exists on the Nexus. Games are assumed to exist if the list of games can't be
+     * fetched. */
+    pub async fn is_known_game(&self, game: &str) -> bool {
+        if let Some(true) = self.cache.games.contains(game).await {
+            return true;
+        }
+        // The game might have been added since the list was fetched
+        if let Err(e) = self.refresh_games(RECHECK_GAMES_AFTER_SECS).await {
+            self.logger.log(format!("Unable to fetch list of games: {e}"));
+            return true;
+        }
+        self.cache.games.contains(game).await.unwrap_or(true)
+    }
+
+    pub async fn tracked_mods(&self) -> Result<(), ApiError> {
+        let tracked = TrackedMods::request(&self.client, &[]).await?;
+        self.cache.tracked.replace(tracked).await;
+        Ok(())
+    }
+
+    pub async fn track(&self, game: &str, mod_id: u32) -> Result<(), ApiError> {
+        let endpoint = util::format::vec_with_format_string(TRACK_FORMAT_STRING, &[game]);
+        self.client.send_api_post(&endpoint, &[("mod_id", &mod_id.to_string())]).await?.error_for_status()?;
+        self.cache.tracked.insert(game, mod_id).await;
+        Ok(())
+    }
+
+    pub async fn untrack(&self, game: &str, mod_id: u32) -> Result<(), ApiError> {
+        let endpoint = util::format::vec_with_format_string(TRACK_FORMAT_STRING, &[game]);
+        self.client
+            .send_api_delete(&endpoint, &[("mod_id", &mod_id.to_string())])
+            .await?
+            .error_for_status()?;
+        self.cache.tracked.remove(game, mod_id).await;
+        Ok(())
+    }
+
+    // Returns whether the mod is tracked afterwards
+    pub async fn toggle_tracking(&self, game: &str, mod_id: u32) -> Result<bool, ApiError> {
+        match self.cache.tracked.contains(game, mod_id).await {
+            true => self.untrack(game, mod_id).await.map(|_| false),
+            false => self.track(game, mod_id).await.map(|_| true),
+        }
     }
 
     /* Searches for a file matching this
@@ -131,39 +405,94 @@ impl Query {
         }
     }
 
-    /* This is unused but should work. Most API requests are easy to implement with serde & traits, but this lacks UI
-     * and a sufficiently compelling use case.
-     * For example, premium users could search and install mods directly through this application.
-     * (Others would have to visit the Nexus, as only premium users can generate download URLs without getting a nxm://
-     * URL from the website.) */
-    #[allow(dead_code)]
+    // Searches the mods of a game by name. The search endpoint takes the numeric id of the game instead of its domain.
+    pub async fn search(&self, game: &str, terms: &str) -> Result<Search, ApiError> {
+        if !self.is_known_game(game).await {
+            return Err(ApiError::UnknownGame { game: game.to_string() });
+        }
+        let Some(game_info) = self.cache.games.get(game).await else {
+            return Err(ApiError::UnknownGame { game: game.to_string() });
+        };
+        let search_query = SearchQuery {
+            terms: terms.split_whitespace().map(|t| t.to_string()).collect(),
+            game_id: game_info.id,
+            blocked_tags: vec![],
+            blocked_authors: vec![],
+            include_adult: false,
+        };
+        search_query.send(self.clone()).await
+    }
+
+    pub async fn collection_revision(
+        &self,
+        game: &str,
+        slug: &str,
+        revision: u32,
+    ) -> Result<Arc<CollectionRevision>, ApiError> {
+        let body = json!({
+            "query": COLLECTION_REVISION_QUERY,
+            "variables": { "slug": slug, "revision": revision, "domainName": game },
+        });
+        let resp = self.client.send_graphql_request(self.config.endpoints.graphql.clone(), &body).await?;
+        let resp: GraphQlResponse<CollectionRevisionData> =
+            self.client.read_json(self.config.endpoints.graphql.as_str(), resp).await?;
+        Ok(self.cache.collection.set(resp.into_result()?.collection_revision).await)
+    }
+
     pub async fn mod_search(&self, query: String) -> Result<Search, ApiError> {
-        let base: Url = Url::parse(SEARCH_URL).unwrap();
-        let url = base.join(&query).unwrap();
-        let builder = self.client.build_request(url)?;
-        Ok(builder.send().await?.json().await?)
+        // The search endpoint doesn't use the API quota, so it doesn't go through Client::send()
+        if self.client.offline.is_on() {
+            return Err(ApiError::Offline);
+        }
+        let url = self.config.endpoints.search.join(&query)?;
+        let builder = self.client.build_request(url.clone())?;
+        self.client.read_json(url.as_str(), builder.send().await?).await
     }
 }
 
 pub trait Queriable: DeserializeOwned {
     const FORMAT_STRING: &'static str;
 
-    /* TODO don't crash if server returns unexpected response, log response instead.
-     * Currently unimplemented because the UI is unable to wrap long messages. */
     async fn request(client: &Client, params: &[&str]) -> Result<Self, ApiError> {
         let endpoint = util::format::vec_with_format_string(Self::FORMAT_STRING, params);
-        let resp = client.send_api_request(&endpoint).await?.error_for_status()?;
-        client.request_counter.push(resp.headers()).await;
+        let resp = client.send_api_request(&endpoint).await?;
 
-        Ok(serde_json::from_value::<Self>(resp.json().await?)?)
+        client.read_json(&endpoint, resp).await
+    }
+
+    /* Like request(), but revalidates a cached response with its validators. NotModified is only returned if there
+     * were validators to send, so it always means that there's a cached response to use. */
+    async fn request_if_modified(
+        client: &Client,
+        params: &[&str],
+        validators: &Validators,
+    ) -> Result<Conditional<Self>, ApiError> {
+        let endpoint = util::format::vec_with_format_string(Self::FORMAT_STRING, params);
+        let resp = client.send_conditional_api_request(&endpoint, validators).await?;
+        if resp.status() == StatusCode::NOT_MODIFIED && !validators.is_empty() {
+            return Ok(Conditional::NotModified);
+        }
+        let validators = Validators::from_headers(resp.headers());
+
+        Ok(Conditional::Modified(client.read_json(&endpoint, resp).await?, validators))
     }
 }
+
+pub enum Conditional<T> {
+    Modified(T, Validators),
+    NotModified,
+}
 #[cfg(test)]
 mod tests {
-    use crate::api::{ApiError, Client, Query};
-    use crate::cache::Cache;
+    use crate::api::mock_server::MockEnv;
+    use crate::api::{ApiError, Client, EndorseStatus, FileList, ModList, Queriable, Query};
+    use crate::cache::{Cache, Cacheable};
+    use crate::config::DataPath;
+    use crate::extract::{InstalledMod, ModDirectory, ModRepository};
+    use crate::util::time;
     use crate::ConfigBuilder;
     use crate::Logger;
+    use serde::Deserialize;
     use std::sync::Arc;
 
     #[tokio::test]
@@ -172,7 +501,7 @@ mod tests {
 
         let logger = Logger::default();
         let cache = Cache::new(config.clone(), logger.clone()).await.unwrap();
-        let client = Client::new(&config).await;
+        let client = Client::new(&config).await.unwrap();
         let query = Query::new(cache.clone(), client.clone(), config.clone(), logger.clone());
 
         let game = "morrowind";
@@ -187,4 +516,296 @@ mod tests {
             },
         }
     }
+
+    #[tokio::test]
+    async fn md5search_with_local_server() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        let game = "morrowind";
+        let mod_id = 46599;
+        let file_id = 1000014318;
+        let file_name = "GH TR - PT Meshes-46599-1-01-1556986716.7z";
+        let file = env.server.add_file(game, mod_id, file_id, file_name, b"not really an archive");
+
+        let res = env.query.md5search(game, &file.md5(), file_name, file_id).await?;
+        assert_eq!(res.file_details.md5, file.md5());
+        assert_eq!(
+            env.cache.mod_info.get(game, mod_id).await.unwrap().name.as_deref(),
+            Some("Graphic Herbalism - MWSE and OpenMW Edition")
+        );
+
+        match env.query.md5search(game, "0123456789abcdef", file_name, file_id).await {
+            Err(ApiError::UnexpectedResponse { response }) if response.status == 404 => Ok(()),
+            _ => panic!("Searching for an unknown hash should fail"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pause_when_quota_is_low() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        env.server.set_remaining(5, 1);
+
+        env.query.mod_info("morrowind", 46599).await?;
+        let counter = &env.client.request_counter;
+        assert_eq!(Some(0), counter.counter.read().await.daily_remaining);
+        assert!(counter.blocked_until().await.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rate_limited() {
+        let env = MockEnv::new().await;
+        env.server.set_remaining(0, 0);
+
+        match env.query.file_list("morrowind", 46599).await {
+            Err(ApiError::RateLimited { retry_after }) => {
+                assert!(retry_after > time::now());
+                assert_eq!(Some(retry_after), env.client.request_counter.blocked_until().await);
+            }
+            _ => panic!("Request should have been rate limited"),
+        }
+    }
+
+    #[tokio::test]
+    async fn revalidate_file_list() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        let (game, mod_id) = ("morrowind", 46599);
+
+        let fetched = env.query.file_list(game, mod_id).await?;
+        let path = DataPath::FileList(&env.config, game, mod_id);
+        assert!(FileList::load_validators(path).await.etag.is_some());
+
+        // The server answers with 304 Not Modified, so the cached file list is kept
+        let revalidated = env.query.file_list(game, mod_id).await?;
+        assert!(Arc::ptr_eq(&fetched, &revalidated));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn offline_answers_from_cache() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        let (game, mod_id) = ("morrowind", 46599);
+        let fetched = env.query.file_list(game, mod_id).await?;
+        let request_count = env.server.requests().len();
+
+        env.client.offline.set(true);
+        assert!(Arc::ptr_eq(&fetched, &env.query.file_list(game, mod_id).await?));
+        assert!(matches!(env.query.mod_info(game, mod_id).await, Err(ApiError::Offline)));
+        assert!(matches!(env.query.tracked_mods().await, Err(ApiError::Offline)));
+        assert_eq!(request_count, env.server.requests().len());
+        Ok(())
+    }
+
+    // A response that doesn't match the expected format is saved for bug reports
+    #[tokio::test]
+    async fn unexpected_response() {
+        #[derive(Debug, Deserialize)]
+        struct Unexpected {
+            #[allow(dead_code)]
+            missing_field: u32,
+        }
+        impl Queriable for Unexpected {
+            const FORMAT_STRING: &'static str = "users/validate.json";
+        }
+
+        let env = MockEnv::new().await;
+        match Unexpected::request(&env.client, &[]).await {
+            Err(ApiError::UnexpectedResponse { response }) => {
+                assert_eq!("users/validate.json", response.endpoint);
+                assert_eq!(200, response.status);
+                assert!(response.excerpt.contains("mockuser"));
+                let path = response.saved_to.unwrap();
+                assert!(path.starts_with(env.config.cache_for_profile()));
+                let saved = std::fs::read_to_string(path).unwrap();
+                assert!(saved.contains("missing field `missing_field`"));
+                assert!(saved.contains("mockuser"));
+            }
+            res => panic!("Expected an unexpected response error, got {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn coalesce_identical_requests() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        let (game, mod_id) = ("morrowind", 46599);
+
+        let (first, second) = tokio::join!(env.query.file_list(game, mod_id), env.query.file_list(game, mod_id));
+        assert!(Arc::ptr_eq(&first?, &second?));
+        let (first, second) = tokio::join!(env.query.mod_info(game, mod_id), env.query.mod_info(game, mod_id));
+        assert!(Arc::ptr_eq(&first?, &second?));
+
+        let requests = env.server.requests();
+        assert_eq!(1, requests.iter().filter(|r| r.ends_with(&format!("mods/{mod_id}/files.json"))).count());
+        assert_eq!(1, requests.iter().filter(|r| r.ends_with(&format!("mods/{mod_id}.json"))).count());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn validate_apikey() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        env.server.set_premium(true);
+
+        let details = env.query.validate().await?;
+        assert_eq!("mockuser", details.name);
+        assert!(env.cache.account.is_premium().await);
+
+        // The account details are remembered across restarts
+        let cache = Cache::new(env.config.clone(), env.logger.clone()).await.unwrap();
+        assert_eq!(Some(1234), cache.account.get().await.map(|d| d.user_id));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn revoked_apikey() {
+        let env = MockEnv::new().await;
+        env.server.revoke_apikey();
+
+        assert!(matches!(env.query.validate().await, Err(ApiError::Unauthorized)));
+        // The client went offline, so the rejected key isn't sent again
+        assert!(env.client.offline.is_on());
+        assert!(!env.client.offline.set(false));
+        let requests = env.server.requests().len();
+        assert!(matches!(env.query.mod_info("morrowind", 46599).await, Err(ApiError::Offline)));
+        assert_eq!(requests, env.server.requests().len());
+        assert!(env.cache.account.get().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn endorse_and_abstain() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        let (game, mod_id) = ("morrowind", 46599);
+
+        assert_eq!(EndorseStatus::Endorsed, env.query.toggle_endorsement(game, mod_id).await?);
+        assert_eq!("Endorsed", env.server.endorse_status(game, mod_id));
+        assert_eq!(Some(EndorseStatus::Endorsed), env.query.endorse_status(game, mod_id).await);
+
+        assert_eq!(EndorseStatus::Abstained, env.query.toggle_endorsement(game, mod_id).await?);
+        assert_eq!("Abstained", env.server.endorse_status(game, mod_id));
+        assert_eq!(Some(EndorseStatus::Abstained), env.query.endorse_status(game, mod_id).await);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn endorse_installed() {
+        let env = MockEnv::new().await;
+        let week = 7 * 24 * 60 * 60;
+        let installed = |mod_id, installed_timestamp| {
+            ModDirectory::Nexus(Arc::new(InstalledMod {
+                game: "morrowind".to_string(),
+                mod_id,
+                file_id: mod_id as u64,
+                name: None,
+                mod_name: None,
+                version: None,
+                category_id: None,
+                category_name: None,
+                installation_file: format!("{mod_id}.7z"),
+                repository: ModRepository::Nexus,
+                last_update_check: Default::default(),
+                update_status: Default::default(),
+                installed_timestamp,
+            }))
+        };
+        {
+            let mut mods = env.cache.installed.mods.write().await;
+            mods.insert("old".to_string(), installed(46599, time::now() - week - 1));
+            mods.insert("recent".to_string(), installed(39350, time::now()));
+        }
+
+        let to_endorse = env.query.installed_to_endorse(week).await;
+        assert_eq!(1, to_endorse.len());
+        assert_eq!(1, env.query.endorse_installed(to_endorse).await);
+        assert_eq!("Endorsed", env.server.endorse_status("morrowind", 46599));
+        assert!(!env.server.requests().iter().any(|r| r.contains("39350")));
+
+        // Already endorsed mods aren't offered again
+        assert!(env.query.installed_to_endorse(week).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn track_and_untrack() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        env.server.track("skyrimspecialedition", 266);
+
+        env.query.tracked_mods().await?;
+        assert!(env.cache.tracked.contains("skyrimspecialedition", 266).await);
+
+        assert!(env.query.toggle_tracking("morrowind", 46599).await?);
+        assert!(env.server.is_tracked("morrowind", 46599));
+        assert!(env.cache.tracked.contains("morrowind", 46599).await);
+
+        assert!(!env.query.toggle_tracking("morrowind", 46599).await?);
+        assert!(!env.server.is_tracked("morrowind", 46599));
+        assert!(!env.cache.tracked.contains("morrowind", 46599).await);
+
+        // The list is saved so it's available before the next refresh
+        let cache = Cache::new(env.config.clone(), env.logger.clone()).await.unwrap();
+        assert_eq!(1, cache.tracked.mods.read().await.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn known_games() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        assert_eq!(None, env.cache.games.contains("morrowind").await);
+
+        env.query.refresh_games(0).await?;
+        assert_eq!(Some(true), env.cache.games.contains("morrowind").await);
+        assert!(env.query.is_known_game("morrowind").await);
+        assert!(!env.query.is_known_game("notagame").await);
+        assert_eq!(Some("Buildings".to_string()), env.cache.games.category_name("morrowind", 2).await);
+
+        // A recently fetched list isn't fetched again
+        let requests = env.server.requests().len();
+        env.query.refresh_games(60).await?;
+        assert_eq!(requests, env.server.requests().len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_mods() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+
+        let search = env.query.search("morrowind", "graphic herbalism").await?;
+        assert_eq!(vec![46599], search.results.iter().map(|r| r.mod_id).collect::<Vec<u32>>());
+        assert_eq!("Stuporstar", search.results[0].username);
+
+        assert!(env.query.search("morrowind", "nothing matches this").await?.results.is_empty());
+        assert!(matches!(env.query.search("notagame", "herbalism").await, Err(ApiError::UnknownGame { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_changelogs() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+
+        let changelogs = env.query.changelogs("morrowind", 46599).await?;
+        let newer: Vec<&str> = changelogs.newer_than(Some("1.02")).into_iter().map(|(v, _)| v).collect();
+        assert_eq!(vec!["1.04", "1.03"], newer);
+        assert!(env.query.changelogs("morrowind", 39350).await?.versions.is_empty());
+
+        // The changelogs are saved along with the time they were fetched
+        let cache = Cache::new(env.config.clone(), env.logger.clone()).await.unwrap();
+        let cached = cache.changelogs.get("morrowind", 46599).await.unwrap();
+        assert_eq!(4, cached.versions.len());
+        assert!(cached.fetched > 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn browse_mod_lists() -> Result<(), ApiError> {
+        let env = MockEnv::new().await;
+        let game = "morrowind";
+        let known = env.query.mod_info(game, 46599).await?;
+
+        let latest = env.query.mod_list(game, ModList::LatestUpdated).await?;
+        assert_eq!(vec![46599, 39350], latest.iter().map(|mi| mi.mod_id).collect::<Vec<u32>>());
+
+        // Known mods keep their cached mod info, and the others are only cached in memory
+        assert!(Arc::ptr_eq(&known, &latest[0]));
+        let name = env.cache.mod_info.get(game, 39350).await.and_then(|mi| mi.name.clone());
+        assert_eq!(Some("Fair Magicka Regen".to_string()), name);
+        assert!(!env.server.requests().iter().any(|r| r.ends_with("39350.json")));
+        assert!(!env.config.metadata_dir().join("morrowind/mod_info/39350.json.zst").exists());
+        Ok(())
+    }
 }
@@ -1,25 +1,56 @@
-use reqwest::header::HeaderMap;
+use crate::util::time;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 #[derive(Debug, Default)]
 pub struct Counter {
     pub hourly_remaining: Option<u16>,
     pub daily_remaining: Option<u16>,
+    pub hourly_reset: Option<u64>, // unix timestamps
+    pub daily_reset: Option<u64>,
+    pub limited_until: Option<u64>, // set when the API responds with 429 Too Many Requests
+}
+
+impl Counter {
+    /* Nexus allows a daily amount of requests. Once those are used up, an hourly quota applies.
+     * We're only out of requests when both are (nearly) exhausted, in which case the next reset of either restores
+     * the quota. Returns None if requests are allowed. */
+    pub fn blocked_until(&self, reserve: u16, now: u64) -> Option<u64> {
+        if let Some(t) = self.limited_until {
+            if t > now {
+                return Some(t);
+            }
+        }
+        let hourly_low = self.hourly_remaining.is_some_and(|r| r <= reserve);
+        let daily_low = self.daily_remaining.is_some_and(|r| r <= reserve);
+        if hourly_low && daily_low {
+            let resets = [self.hourly_reset, self.daily_reset].into_iter().flatten();
+            // A reset that has passed restored the quota, even if no response has refreshed the counter since
+            if resets.clone().any(|t| t <= now) {
+                return None;
+            }
+            return resets.min();
+        }
+        None
+    }
 }
 
 #[derive(Clone)]
 pub struct RequestCounter {
     pub counter: Arc<RwLock<Counter>>,
     pub has_changed: Arc<AtomicBool>,
+    pub reserve: u16, // amount of requests that are left unused
 }
 
 impl RequestCounter {
-    pub fn new() -> Self {
+    pub fn new(reserve: u16) -> Self {
         Self {
             counter: Arc::new(RwLock::new(Counter::default())),
             has_changed: Arc::new(AtomicBool::from(false)),
+            reserve,
         }
     }
 
@@ -32,6 +63,78 @@ impl RequestCounter {
         if let Some(value) = headers.get("x-rl-hourly-remaining") {
             counter.hourly_remaining = value.to_str().map_or(None, |v| str::parse::<u16>(v).ok());
         }
+        if let Some(value) = headers.get("x-rl-daily-reset") {
+            counter.daily_reset = value.to_str().ok().and_then(time::parse_timestamp);
+        }
+        if let Some(value) = headers.get("x-rl-hourly-reset") {
+            counter.hourly_reset = value.to_str().ok().and_then(time::parse_timestamp);
+        }
         self.has_changed.store(true, Ordering::Relaxed);
     }
+
+    /* Called when the API responds with 429 Too Many Requests. Returns the time after which requests are allowed again.
+     * The Retry-After header is preferred, falling back to the next hourly reset. */
+    pub async fn set_rate_limited(&self, headers: &HeaderMap) -> u64 {
+        let now = time::now();
+        let mut counter = self.counter.write().await;
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(|secs| now + secs)
+            .or(counter.hourly_reset.filter(|t| *t > now))
+            .unwrap_or(now + 60);
+        counter.limited_until = Some(retry_after);
+        self.has_changed.store(true, Ordering::Relaxed);
+        retry_after
+    }
+
+    pub async fn blocked_until(&self) -> Option<u64> {
+        self.counter.read().await.blocked_until(self.reserve, time::now())
+    }
+
+    // Delays the caller until the remaining quota is above the reserve. Requests are queued in the meantime.
+    pub async fn wait_for_quota(&self) {
+        while let Some(t) = self.blocked_until().await {
+            self.has_changed.store(true, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_secs(t.saturating_sub(time::now()).max(1))).await;
+        }
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn blocked_when_both_quotas_low() {
+        let now = 1000;
+        let mut counter = Counter {
+            hourly_remaining: Some(5),
+            daily_remaining: Some(500),
+            hourly_reset: Some(now + 600),
+            daily_reset: Some(now + 3600),
+            limited_until: None,
+        };
+        assert_eq!(None, counter.blocked_until(10, now));
+
+        counter.daily_remaining = Some(10);
+        assert_eq!(Some(now + 600), counter.blocked_until(10, now));
+
+        // The hourly reset has passed but the counter hasn't been refreshed yet
+        assert_eq!(None, counter.blocked_until(10, now + 600));
+        assert_eq!(None, counter.blocked_until(10, now + 3601));
+    }
+
+    #[test]
+    fn blocked_after_too_many_requests() {
+        let now = 1000;
+        let counter = Counter {
+            limited_until: Some(now + 30),
+            ..Default::default()
+        };
+        assert_eq!(Some(now + 30), counter.blocked_until(10, now));
+        assert_eq!(None, counter.blocked_until(10, now + 30));
+    }
 }
@@ -1,13 +1,19 @@
 use super::UpdateStatus;
-use super::{Client, FileList, Queriable};
+use super::{Client, FileList, Queriable, UpdateProgress};
 use crate::api::{Query, Updated};
 use crate::cache::{Cache, ModFileMetadata};
+use crate::util::time;
 use crate::Config;
 use crate::Logger;
+use futures_util::{stream, StreamExt};
+use std::collections::HashSet;
+use std::pin::pin;
 use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::task;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::{task, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
 pub struct UpdateChecker {
@@ -16,6 +22,8 @@ pub struct UpdateChecker {
     config: Arc<Config>,
     logger: Logger,
     query: Query,
+    pub progress: UpdateProgress,
+    cancel_token: Arc<Mutex<CancellationToken>>,
 }
 
 impl UpdateChecker {
@@ -26,6 +34,8 @@ impl UpdateChecker {
             config,
             logger,
             query,
+            progress: Default::default(),
+            cancel_token: Default::default(),
         }
     }
 
@@ -59,116 +69,326 @@ impl UpdateChecker {
         }
     }
 
+    // Unlike ignore_file(), the updates stay ignored when the mod gets new files
+    pub async fn ignore_file_permanently(&self, file_id: u64) {
+        if let Some(mfd) = self.cache.metadata_index.get_by_file_id(&file_id).await {
+            let status = UpdateStatus::IgnoredPermanently(time::now());
+            mfd.propagate_update_status(&self.config, &self.logger, &status).await;
+            self.cache.archives.has_changed.store(true, Ordering::Relaxed);
+            self.cache.installed.has_changed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // Ignores the files of the mod that have been downloaded. Files downloaded later are checked as usual.
+    pub async fn ignore_mod_permanently(&self, game: &str, mod_id: u32) {
+        if let Some(files) = self.cache.metadata_index.get_modfiles(game, &mod_id).await {
+            let status = UpdateStatus::IgnoredPermanently(time::now());
+            for mfd in files {
+                mfd.propagate_update_status(&self.config, &self.logger, &status).await;
+            }
+            self.cache.archives.has_changed.store(true, Ordering::Relaxed);
+            self.cache.installed.has_changed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // Clears both kinds of ignores from the files of the mod, which are then checked for updates again
+    pub async fn unignore_mod(&self, game: &str, mod_id: u32) {
+        if let Some(files) = self.cache.metadata_index.get_modfiles(game, &mod_id).await {
+            for mfd in &files {
+                let status = mfd.update_status.to_enum();
+                if matches!(status, UpdateStatus::IgnoredUntil(_) | UpdateStatus::IgnoredPermanently(_)) {
+                    mfd.propagate_update_status(&self.config, &self.logger, &UpdateStatus::UpToDate(0)).await;
+                }
+            }
+            self.update_mod(game.to_string(), mod_id, files).await;
+        }
+    }
+
+    // The check runs in the background, so that the UI isn't blocked while waiting for the API
     pub async fn update_all(&self) {
-        match SystemTime::now().duration_since(UNIX_EPOCH) {
-            // If less than a month has passed since previous update we can use the API endpoint for mod updates
-            Ok(time) => {
-                // TODO the updated timestamp is per profile and doesn't take into account user moved files
-                let t_diff = time.as_secs() - self.cache.last_update_check.load(Ordering::Relaxed);
-                // this is how many seconds are in 28 days
-                if t_diff < 2419200 {
-                    let me = self.clone();
-                    task::spawn(async move {
-                        let mods_by_game = me.cache.metadata_index.by_game_and_mod_sorted.read().await;
-                        if let Err(e) = me.cache.save_last_updated(time.as_secs()).await {
-                            me.logger.log(format!("Failed to save last updated status: {}", e));
-                        }
+        let me = self.clone();
+        task::spawn(async move {
+            me.check_all().await;
+        });
+    }
+
+    // Stops the running check of all mods. Mods that are being checked finish first.
+    pub fn cancel(&self) {
+        // The lock is held so that a check that's starting can't miss the cancellation
+        let cancel_token = self.cancel_token.lock().unwrap();
+        if self.progress.is_running() {
+            cancel_token.cancel();
+            self.logger.log("Cancelling the update check...");
+        }
+    }
+
+    /* Checks every mod and returns once the results have been saved to the metadata. Mods are checked update_concurrency
+     * at a time, and API requests wait while the remaining quota is below the configured reserve. Returns the games that
+     * couldn't be checked completely, whose files might have outdated update statuses. */
+    pub async fn check_all(&self) -> HashSet<String> {
+        // The token is replaced together with starting the check, so that cancel() always reaches the running one
+        let cancel_token = CancellationToken::new();
+        {
+            let mut current = self.cancel_token.lock().unwrap();
+            if !self.progress.start() {
+                self.logger.log("Updates are already being checked.");
+                return HashSet::new();
+            }
+            *current = cancel_token.clone();
+        }
+
+        // The index is copied so that it isn't locked while waiting for the API
+        let mods_by_game = self.cache.metadata_index.by_game_and_mod_sorted.read().await.clone();
+        let mut to_check = vec![];
+        // Games whose time of last check is saved if each of their mods was checked
+        let mut checked_games = vec![];
+        let mut failed_games = HashSet::new();
 
-                        /* The updated mod lists are provided per game and sorted by mod id
-                         * FileIndex.game_to_mods_map  */
-
-                        for (game, mod_map) in mods_by_game.iter() {
-                            match Updated::request(&me.client, &[game]).await {
-                                Ok(updated_mods) => {
-                                    // Uncomment to save Updated lists
-                                    //if let Err(e) =
-                                    //    updated_mods.save(me.config.path_for(DataType::Updated(&game))).await
-                                    //{
-                                    //    me.logger.log(format!("Unable to save update list for {game}: {}", e));
-                                    //}
-
-                                    // Local and updated mods are sorted so we can iterate in parallel
-                                    for (mod_id, files) in mod_map {
-                                        for upd in &updated_mods.updates {
-                                            if upd.mod_id == *mod_id {
-                                                me.update_mod(game.clone(), *mod_id, files.clone()).await;
-                                            }
-                                            if upd.mod_id > *mod_id {
-                                                break;
-                                            }
-                                        }
-                                    }
+        /* Offline, only the cached file lists can be checked. The time of the last check isn't saved, so the next check
+         * that's done online knows which mods have been updated since. */
+        let offline = self.client.offline.is_on();
+        /* Each game keeps the time of its last successful check. Games checked within the past 28 days only need the
+         * mods on the list of recently updated mods checked, others have each of their mods checked. The time is taken
+         * before the requests are sent, so that mods updated during the check are checked again next time. */
+        let now = time::now();
+        for (game, mod_map) in mods_by_game.iter() {
+            if cancel_token.is_cancelled() {
+                break;
+            }
+            // this is how many seconds are in 28 days
+            if offline || now.saturating_sub(self.cache.last_update_check.get(game).await) >= 2419200 {
+                if !offline {
+                    self.logger.log(format!("Over a month since {game} was checked for updates, checking each mod."));
+                }
+                for (mod_id, files) in mod_map {
+                    to_check.push((game.clone(), *mod_id, files.clone(), false));
+                }
+            } else {
+                match Updated::request(&self.client, &[game]).await {
+                    Ok(updated_mods) => {
+                        // Local and updated mods are sorted so we can iterate in parallel
+                        for (mod_id, files) in mod_map {
+                            for upd in &updated_mods.updates {
+                                if upd.mod_id == *mod_id {
+                                    to_check.push((game.clone(), *mod_id, files.clone(), true));
                                 }
-                                Err(e) => {
-                                    me.logger.log(format!("Unable to fetch update lists for {game}: {}", e));
-                                    return;
+                                if upd.mod_id > *mod_id {
+                                    break;
                                 }
                             }
                         }
-                        if let Err(e) = me.cache.save_last_updated(time.as_secs()).await {
-                            me.logger.log(format!("Failed to save last_updated: {e}"));
-                        }
-                    });
-                } else {
-                    self.logger.log("Over a month since last update check, checking each mod.");
-                    if let Err(e) = self.cache.save_last_updated(time.as_secs()).await {
-                        self.logger.log(format!("Failed to save last updated status: {}", e));
                     }
-                    for (game, mods) in self.cache.metadata_index.by_game_and_mod_sorted.read().await.iter() {
-                        for (mod_id, files) in mods {
-                            self.update_mod(game.clone(), *mod_id, files.clone()).await;
-                        }
+                    Err(e) => {
+                        self.logger.log(format!("Unable to fetch update lists for {game}: {}", e));
+                        failed_games.insert(game.clone());
+                        continue;
                     }
                 }
             }
-            // This is a ridiculous error case to handle, but avoids an unwrap()
-            Err(e) => {
-                self.logger.log(format!("WARNING: Refusing to update, system time is before Unix epoch: {}", e));
+            checked_games.push(game);
+        }
+
+        self.progress.set_total(to_check.len());
+        // Once cancelled, no more mods are started, but the ones being checked are waited for
+        let checks = stream::iter(to_check)
+            .take_until(cancel_token.cancelled())
+            .map(|(game, mod_id, files, recently_updated)| async move {
+                let succeeded = self.check_files(&game, mod_id, &files, recently_updated).await;
+                let outdated =
+                    files.iter().any(|mfd| matches!(mfd.update_status.to_enum(), UpdateStatus::OutOfDate(_)));
+                (game, succeeded, outdated)
+            })
+            .buffer_unordered(self.config.update_concurrency);
+        let mut checks = pin!(checks);
+        while let Some((game, succeeded, outdated)) = checks.next().await {
+            self.progress.add_checked(outdated);
+            if !succeeded {
+                failed_games.insert(game);
+            }
+        }
+
+        let cancelled = cancel_token.is_cancelled();
+        let had_errors = !failed_games.is_empty();
+        if cancelled {
+            // Some of their mods might not have been reached
+            failed_games.extend(checked_games.into_iter().cloned());
+        } else if !offline {
+            for game in checked_games.into_iter().filter(|game| !failed_games.contains(*game)) {
+                self.cache.last_update_check.set(game, now).await;
+            }
+        }
+        let (checked, total, outdated) = self.progress.get();
+        self.progress.finish();
+        let mut summary = format!("Checked {checked}/{total} mods for updates, {outdated} outdated.");
+        if cancelled {
+            summary = format!("Update check cancelled. {summary}");
+        }
+        if offline {
+            summary.push_str(" Only the cached file lists were checked.");
+        }
+        if had_errors {
+            summary.push_str(" Some mods couldn't be checked, see the errors above.");
+        }
+        self.logger.log(summary);
+        failed_games
+    }
+
+    /* Used by the daemon. Runs check_all() once the interval has passed since the previous check, including checks made
+     * from the TUI. Files that have become out of date are logged and passed on to the configured update_command. */
+    pub async fn schedule(&self, interval: Duration) {
+        // Checks made offline don't save their time, so it's also kept here
+        let mut last_run = 0;
+        loop {
+            let previous = self.cache.last_update_check.latest().await.max(last_run);
+            let wait = (previous + interval.as_secs()).saturating_sub(time::now());
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            last_run = time::now();
+
+            let outdated = self.check_for_new_updates().await;
+            if outdated.is_empty() {
+                continue;
+            }
+            let summary = format!("{} files are out of date: {}", outdated.len(), outdated.join(", "));
+            self.logger.log(&summary);
+            self.run_update_command(&summary).await;
+        }
+    }
+
+    // Runs check_all() and returns the names of the files that weren't out of date before it
+    async fn check_for_new_updates(&self) -> Vec<String> {
+        let mut already_outdated = HashSet::new();
+        for mfd in self.all_files().await {
+            if let UpdateStatus::OutOfDate(_) = mfd.update_status.to_enum() {
+                already_outdated.insert(mfd.file_id);
+            }
+        }
+        self.check_all().await;
+
+        let mut outdated = vec![];
+        for mfd in self.all_files().await {
+            if let UpdateStatus::OutOfDate(_) = mfd.update_status.to_enum() {
+                if !already_outdated.contains(&mfd.file_id) {
+                    let name = match mfd.name().await {
+                        Some(name) => name,
+                        None => mfd.file_id.to_string(),
+                    };
+                    outdated.push(name);
+                }
             }
+        }
+        outdated
+    }
+
+    async fn all_files(&self) -> Vec<Arc<ModFileMetadata>> {
+        let mods_by_game = self.cache.metadata_index.by_game_and_mod_sorted.read().await;
+        mods_by_game.values().flat_map(|mods| mods.values().flatten().cloned()).collect()
+    }
+
+    // The summary is passed as the last argument, so that commands such as notify-send can show it as is
+    async fn run_update_command(&self, summary: &str) {
+        let Some((program, args)) = self.config.update_command.split_first() else {
+            return;
         };
-        self.logger.log("Finished checking updates.");
+        match Command::new(program).args(args).arg(summary).status().await {
+            Ok(status) if !status.success() => self.logger.log(format!("update_command {program} failed: {status}")),
+            Err(e) => self.logger.log(format!("Unable to run update_command {program}: {e}")),
+            Ok(_) => {}
+        }
     }
 
-    pub async fn update_mod(&self, game: String, mod_id: u32, files_in_mod: Vec<Arc<ModFileMetadata>>) {
+    /* The check runs in the background. The returned handle can be awaited for it to finish, and tells whether the file
+     * list could be refreshed when needed. A single mod is checked on request, so its mod info is refreshed too. */
+    pub async fn update_mod(
+        &self,
+        game: String,
+        mod_id: u32,
+        files_in_mod: Vec<Arc<ModFileMetadata>>,
+    ) -> JoinHandle<bool> {
         let me = self.clone();
-        task::spawn(async move {
-            let mut needs_refresh = false;
-            let mut checked: Vec<(Arc<ModFileMetadata>, UpdateStatus)> = vec![];
-            /* First try to check updates with cached values.
-             * If the UpdateStatus is already OutOfDate or HasNewFile, there's no reason to query the API.
-             * Only query the API if a file is still reported as UpToDate.
-             */
-            if let Some(fl) = me.cache.file_lists.get(game.clone(), mod_id).await {
-                checked = me.check_mod(&files_in_mod, &fl).await;
-                for (_fdata, status) in &checked {
-                    if let UpdateStatus::UpToDate(_) = status {
-                        needs_refresh = true;
-                    }
+        task::spawn(async move { me.check_files(&game, mod_id, &files_in_mod, true).await })
+    }
+
+    /* Mod info is refreshed along with the file list only for mods that have been updated recently, since taking a mod
+     * down updates it, and for mods whose file list can't be fetched. Otherwise checking each mod of a game would use
+     * twice the requests. */
+    async fn check_files(
+        &self,
+        game: &str,
+        mod_id: u32,
+        files_in_mod: &[Arc<ModFileMetadata>],
+        refresh_mod_info: bool,
+    ) -> bool {
+        let mut succeeded = true;
+        let mut needs_refresh = false;
+        let mut checked: Vec<(Arc<ModFileMetadata>, UpdateStatus)> = vec![];
+        let mut unavailable = false;
+        if refresh_mod_info && !self.client.offline.is_on() {
+            unavailable = self.check_availability(game, mod_id).await;
+        }
+        /* First try to check updates with cached values.
+         * If the UpdateStatus is already OutOfDate or HasNewFile, there's no reason to query the API.
+         * Only query the API if a file is still reported as UpToDate.
+         */
+        if let Some(fl) = self.cache.file_lists.get(game, mod_id).await {
+            checked = self.check_mod(files_in_mod, &fl).await;
+            for (_fdata, status) in &checked {
+                if let UpdateStatus::UpToDate(_) = status {
+                    needs_refresh = true;
                 }
-            } else {
-                me.logger.log(format!("Strange, no file list in cache for {mod_id}. Fetching."));
-                needs_refresh = true;
             }
-            if needs_refresh {
-                /* We only need to make one API request per mod, since the response contains info about all files in
-                 * that mod. */
-                match me.query.file_list(&game, mod_id).await {
-                    Ok(fl) => {
-                        checked = me.check_mod(&files_in_mod, &fl).await;
+        } else if !self.client.offline.is_on() {
+            self.logger.log(format!("Strange, no file list in cache for {mod_id}. Fetching."));
+            needs_refresh = true;
+        }
+        if needs_refresh && !self.client.offline.is_on() {
+            /* We only need to make one API request per mod, since the response contains info about all files in
+             * that mod. */
+            match self.query.file_list(game, mod_id).await {
+                Ok(fl) => {
+                    checked = self.check_mod(files_in_mod, &fl).await;
+                }
+                Err(e) => {
+                    self.logger.log(format!("Error when refreshing filelist for {mod_id}: {}", e));
+                    if !refresh_mod_info {
+                        unavailable = self.check_availability(game, mod_id).await;
                     }
-                    Err(e) => {
-                        me.logger.log(format!("Error when refreshing filelist for {mod_id}: {}", e));
+                    // Mods that have been taken down might not have a file list anymore, which would fail every check
+                    if !unavailable {
+                        succeeded = false;
                     }
                 }
             }
-            for (mfd, new_status) in checked {
-                if mfd.update_status.to_enum() != new_status {
-                    mfd.propagate_update_status(&me.config, &me.logger, &new_status).await;
-                }
+        }
+        for (mfd, new_status) in checked {
+            if mfd.update_status.to_enum() != new_status {
+                mfd.propagate_update_status(&self.config, &self.logger, &new_status).await;
             }
-            me.cache.archives.has_changed.store(true, Ordering::Relaxed);
-            me.cache.installed.has_changed.store(true, Ordering::Relaxed);
-        });
+        }
+        self.cache.archives.has_changed.store(true, Ordering::Relaxed);
+        self.cache.installed.has_changed.store(true, Ordering::Relaxed);
+        succeeded
+    }
+
+    /* Refreshes the mod info, which tells whether the mod has been hidden or removed from the Nexus. Saving it updates
+     * the flags of the mod's files. Returns whether the mod is unavailable. A failed request isn't counted as a failed
+     * update check, since the mod info isn't needed for finding updates. */
+    async fn check_availability(&self, game: &str, mod_id: u32) -> bool {
+        let was_unavailable = self.cache.mod_info.get(game, mod_id).await.is_some_and(|mi| mi.is_unavailable());
+        let mod_info = match self.query.mod_info(game, mod_id).await {
+            Ok(mod_info) => mod_info,
+            Err(e) => {
+                self.logger.log(format!("Error when refreshing mod info for {mod_id}: {}", e));
+                return was_unavailable;
+            }
+        };
+        if mod_info.is_unavailable() && !was_unavailable {
+            let name = mod_info.name.clone().unwrap_or(mod_id.to_string());
+            self.logger.log(format!(
+                "Warn: {name} ({game}) is no longer available on the Nexus, its status is \"{}\". Back up its archives.",
+                mod_info.status
+            ));
+        }
+        mod_info.is_unavailable()
     }
 
     /* This is complicated and maybe buggy.
@@ -222,8 +442,9 @@ impl UpdateChecker {
         let latest_remote_time = file_list.files.last().unwrap().uploaded_timestamp;
 
         for mfd in to_check.iter().rev() {
+            mfd.set_replacement(file_list.newest_replacement(mfd.file_id)).await;
             let update_status = mfd.update_status.to_enum();
-            if let UpdateStatus::OutOfDate(_) = update_status {
+            if let UpdateStatus::OutOfDate(_) | UpdateStatus::IgnoredPermanently(_) = update_status {
                 checked.push((mfd.clone(), update_status));
                 continue;
             }
@@ -326,6 +547,7 @@ impl UpdateChecker {
 #[cfg(test)]
 mod tests {
     use super::UpdateStatus;
+    use crate::api::mock_server::MockEnv;
     use crate::api::{ApiError, Client, Query, UpdateChecker};
     use crate::cache::Cache;
     use crate::config::tests::setup_test_env;
@@ -338,7 +560,7 @@ mod tests {
         let config = Arc::new(ConfigBuilder::default().profile(profile).build().unwrap());
         let logger = Logger::default();
         let cache = Cache::new(config.clone(), logger.clone()).await.unwrap();
-        let client = Client::new(&config).await;
+        let client = Client::new(&config).await.unwrap();
         let query = Query::new(cache.clone(), client.clone(), config.clone(), logger.clone());
         (cache.clone(), UpdateChecker::new(cache, client, config, logger, query))
     }
@@ -407,4 +629,130 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn ignored_permanently() -> Result<(), ApiError> {
+        setup_test_env();
+        let game = "morrowind";
+        let mod_id = 46599;
+
+        let (cache, update) = init_structs().await;
+
+        let files = cache.metadata_index.get_modfiles(game, &mod_id).await.unwrap();
+        let file_list = cache.file_lists.get(game, mod_id).await.unwrap();
+        let ignored = files.last().unwrap();
+        ignored.update_status.set(UpdateStatus::IgnoredPermanently(1));
+
+        // The file has updates, but it stays ignored while the other files are out of date
+        for (mfd, status) in update.check_mod(&files, &file_list).await {
+            match mfd.file_id == ignored.file_id {
+                true => assert_eq!(UpdateStatus::IgnoredPermanently(1), status),
+                false => assert!(matches!(status, UpdateStatus::OutOfDate(_))),
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn report_new_updates() {
+        let env = MockEnv::with_archives(&[
+            "GH TR - PT Meshes-46599-1-01-1556986716.7z",
+            "Graphic Herbalism MWSE - OpenMW-46599-1-03-1556986083.7z",
+        ])
+        .await;
+        let update = env.updater();
+
+        // The other file was already out of date
+        assert_eq!(vec!["GH TR - PT Meshes"], update.check_for_new_updates().await);
+        // Files are only reported when they become out of date
+        assert!(update.check_for_new_updates().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn check_all_progress() {
+        let env = MockEnv::with_archives(&[
+            "GH TR - PT Meshes-46599-1-01-1556986716.7z",
+            "Graphic Herbalism MWSE - OpenMW-46599-1-03-1556986083.7z",
+            "Fair Magicka Regen v2B-39350-2-0b.rar",
+        ])
+        .await;
+        let update = env.updater();
+
+        update.check_all().await;
+        assert!(!update.progress.is_running());
+        // Both files of the first mod are out of date, which counts as one outdated mod
+        assert_eq!((2, 2, 1), update.progress.get());
+        // Another check can be started once the previous one has finished
+        assert!(update.progress.start());
+    }
+
+    #[tokio::test]
+    async fn update_checks_per_game() {
+        let game = "morrowind";
+        let env = MockEnv::with_archives(&["GH TR - PT Meshes-46599-1-01-1556986716.7z"]).await;
+        let update = env.updater();
+        let updated = format!("/v1/games/{game}/mods/updated.json");
+
+        // A game that hasn't been checked has each of its mods checked, without refreshing their mod info
+        update.check_all().await;
+        assert!(!env.server.requests().contains(&updated));
+        assert!(!env.server.requests().contains(&format!("/v1/games/{game}/mods/46599.json")));
+        let checked = env.cache.last_update_check.get(game).await;
+        assert!(checked > 0);
+
+        // Afterwards only the recently updated mods are checked
+        update.check_all().await;
+        assert!(env.server.requests().contains(&updated));
+        assert!(env.cache.last_update_check.get(game).await >= checked);
+
+        // Failed checks don't advance the time
+        env.cache.last_update_check.set(game, checked - 60).await;
+        env.server.revoke_apikey();
+        assert!(update.check_all().await.contains(game));
+        assert_eq!(checked - 60, env.cache.last_update_check.get(game).await);
+    }
+
+    #[tokio::test]
+    async fn flag_unavailable_mods() {
+        let game = "morrowind";
+        let mod_id = 46599;
+        let env = MockEnv::with_archives(&["GH TR - PT Meshes-46599-1-01-1556986716.7z"]).await;
+        let update = env.updater();
+        let files = env.cache.metadata_index.get_modfiles(game, &mod_id).await.unwrap();
+
+        assert!(update.update_mod(game.to_string(), mod_id, files.clone()).await.await.unwrap());
+        assert!(!files[0].mod_info().await.unwrap().is_unavailable());
+
+        env.server.set_mod_status(game, mod_id, "hidden");
+        assert!(update.update_mod(game.to_string(), mod_id, files.clone()).await.await.unwrap());
+        let mod_info = files[0].mod_info().await.unwrap();
+        assert!(mod_info.is_unavailable());
+        assert_eq!("hidden", mod_info.status);
+    }
+
+    #[tokio::test]
+    async fn refresh_file_list_from_server() -> Result<(), ApiError> {
+        let game = "morrowind";
+        let mod_id = 46599;
+        let latest_remote_time = 1558643754;
+
+        let env = MockEnv::with_archives(&[
+            "GH TR - PT Meshes-46599-1-01-1556986716.7z",
+            "Graphic Herbalism MWSE - OpenMW-46599-1-03-1556986083.7z",
+        ])
+        .await;
+        let update = env.updater();
+
+        // Nothing is cached yet, so the file list has to be requested
+        assert!(env.cache.file_lists.get(game, mod_id).await.is_none());
+        let files = env.cache.metadata_index.get_modfiles(game, &mod_id).await.unwrap();
+        update.update_mod(game.to_string(), mod_id, files.clone()).await.await.unwrap();
+
+        assert!(env.server.requests().contains(&format!("/v1/games/{game}/mods/{mod_id}/files.json")));
+        assert!(env.cache.file_lists.get(game, mod_id).await.is_some());
+        for mfd in files {
+            assert_eq!(UpdateStatus::OutOfDate(latest_remote_time), mfd.update_status.to_enum());
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/* Progress of the update check that is running, if any. Only one check of all mods runs at a time, and the top bar shows
+ * its progress. */
+#[derive(Clone, Default)]
+pub struct UpdateProgress {
+    running: Arc<AtomicBool>,
+    checked: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>,
+    outdated: Arc<AtomicUsize>,
+    pub has_changed: Arc<AtomicBool>,
+}
+
+impl UpdateProgress {
+    // Returns false if a check is already running
+    pub fn start(&self) -> bool {
+        if self.running.swap(true, Ordering::Relaxed) {
+            return false;
+        }
+        self.checked.store(0, Ordering::Relaxed);
+        self.total.store(0, Ordering::Relaxed);
+        self.outdated.store(0, Ordering::Relaxed);
+        self.has_changed.store(true, Ordering::Relaxed);
+        true
+    }
+
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    // Called once per checked mod, which is outdated if any of its files are
+    pub fn add_checked(&self, outdated: bool) {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        self.outdated.fetch_add(outdated as usize, Ordering::Relaxed);
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self) {
+        self.running.store(false, Ordering::Relaxed);
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    // Checked mods, mods to check, and checked mods with out of date files
+    pub fn get(&self) -> (usize, usize, usize) {
+        (
+            self.checked.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+            self.outdated.load(Ordering::Relaxed),
+        )
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use std::sync::Arc;
 
@@ -6,6 +7,7 @@ pub const UP_TO_DATE: u8 = 0;
 pub const HAS_NEW_FILE: u8 = 1;
 pub const OUT_OF_DATE: u8 = 2;
 pub const IGNORED_UNTIL: u8 = 3;
+pub const IGNORED_PERMANENTLY: u8 = 4;
 pub const INVALID: u8 = u8::MAX;
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
@@ -14,6 +16,8 @@ pub enum UpdateStatus {
     HasNewFile(u64),   // time of user's newest file
     OutOfDate(u64),    // time of user's newest file
     IgnoredUntil(u64), // time of latest file in update list
+    // Until unignored. The time is when updates were ignored.
+    IgnoredPermanently(u64),
     Invalid(u64),
 }
 
@@ -23,6 +27,20 @@ impl Default for UpdateStatus {
     }
 }
 
+// The name of the variant, as used in reports
+impl fmt::Display for UpdateStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            UpdateStatus::UpToDate(_) => "UpToDate",
+            UpdateStatus::HasNewFile(_) => "HasNewFile",
+            UpdateStatus::OutOfDate(_) => "OutOfDate",
+            UpdateStatus::IgnoredUntil(_) => "IgnoredUntil",
+            UpdateStatus::IgnoredPermanently(_) => "IgnoredPermanently",
+            UpdateStatus::Invalid(_) => "Invalid",
+        })
+    }
+}
+
 // Hack to retain backward compatibility with previously serialized data and provide a better API than two atomics
 #[derive(Clone, Debug)]
 pub struct UpdateStatusWrapper {
@@ -55,6 +73,7 @@ impl UpdateStatusWrapper {
             UP_TO_DATE => UpdateStatus::UpToDate(time),
             HAS_NEW_FILE => UpdateStatus::HasNewFile(time),
             IGNORED_UNTIL => UpdateStatus::IgnoredUntil(time),
+            IGNORED_PERMANENTLY => UpdateStatus::IgnoredPermanently(time),
             OUT_OF_DATE => UpdateStatus::OutOfDate(time),
             _ => UpdateStatus::Invalid(0),
         }
@@ -73,6 +92,7 @@ impl UpdateStatusWrapper {
             UpdateStatus::UpToDate(t) => (UP_TO_DATE, t),
             UpdateStatus::HasNewFile(t) => (HAS_NEW_FILE, t),
             UpdateStatus::IgnoredUntil(t) => (IGNORED_UNTIL, t),
+            UpdateStatus::IgnoredPermanently(t) => (IGNORED_PERMANENTLY, t),
             UpdateStatus::OutOfDate(t) => (OUT_OF_DATE, t),
             UpdateStatus::Invalid(_) => (INVALID, 0),
         };
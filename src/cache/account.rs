@@ -0,0 +1,45 @@
+use super::Cacheable;
+use crate::api::UserDetails;
+use crate::config::DataPath;
+use crate::{Config, Logger};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/* The account of the API key, as reported by the Nexus when the key was last validated. It's loaded from disk on
+ * startup, so premium features are known even if the Nexus can't be reached. */
+#[derive(Clone)]
+pub struct Account {
+    config: Arc<Config>,
+    logger: Logger,
+    details: Arc<RwLock<Option<UserDetails>>>,
+    pub has_changed: Arc<AtomicBool>,
+}
+
+impl Account {
+    pub async fn new(config: Arc<Config>, logger: Logger) -> Self {
+        let details = UserDetails::load(DataPath::UserDetails(&config)).await.ok();
+        Self {
+            config,
+            logger,
+            details: Arc::new(RwLock::new(details)),
+            has_changed: Arc::new(true.into()),
+        }
+    }
+
+    pub async fn get(&self) -> Option<UserDetails> {
+        self.details.read().await.clone()
+    }
+
+    pub async fn set(&self, details: UserDetails) {
+        if let Err(e) = details.save(DataPath::UserDetails(&self.config)).await {
+            self.logger.log(format!("Unable to save account details: {e}"));
+        }
+        *self.details.write().await = Some(details);
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    pub async fn is_premium(&self) -> bool {
+        self.details.read().await.as_ref().is_some_and(|d| d.is_premium)
+    }
+}
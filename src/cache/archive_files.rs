@@ -109,6 +109,14 @@ impl ArchiveFiles {
         self.files.read().await.get(file_name).cloned()
     }
 
+    // Entries that only have metadata left don't count, since their archive has been deleted
+    pub async fn contains_file_id(&self, file_id: u64) -> bool {
+        self.files.read().await.values().any(|entry| match entry {
+            ArchiveEntry::File(archive) => archive.mod_data.as_ref().is_some_and(|md| md.file_id == file_id),
+            ArchiveEntry::MetadataOnly(_) => false,
+        })
+    }
+
     pub async fn delete(&self, file_name: &str) {
         let mut lock = self.files.write().await;
         if let Some(_archive_file) = lock.get(file_name) {
@@ -1,7 +1,8 @@
+use reqwest::header::{HeaderMap, ETAG, LAST_MODIFIED};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
-use std::io::{BufReader, Error, Write};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
 use std::{fs, fs::File};
 
 pub trait Cacheable: Serialize + DeserializeOwned + Send
@@ -50,6 +51,52 @@ where
         .await
         .unwrap()
     }
+
+    // The validators are stored next to the response, so they're found by the same path
+    async fn save_validators<T: Into<PathBuf>>(path: T, validators: &Validators) -> Result<(), Error> {
+        let path = validators_path(&path.into());
+        if !validators.is_empty() {
+            return validators.save(path).await;
+        }
+        // Stale validators would make the next request fail to revalidate anyway
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    async fn load_validators<T: Into<PathBuf>>(path: T) -> Validators {
+        Validators::load(validators_path(&path.into())).await.unwrap_or_default()
+    }
+}
+
+/* The ETag and Last-Modified headers of a cached response. They're sent along with the next request for the same
+ * resource, which the server answers with 304 Not Modified if the cached response is still valid. */
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Cacheable for Validators {}
+
+impl Validators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(|v| v.to_string());
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+// foo.json has its validators in foo.validators.json
+fn validators_path(path: &Path) -> PathBuf {
+    path.with_extension("validators.json")
 }
 
 #[cfg(test)]
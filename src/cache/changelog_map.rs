@@ -0,0 +1,53 @@
+use super::Cacheable;
+use crate::api::Changelogs;
+use crate::config::DataPath;
+use crate::util::time;
+use crate::{Config, Logger};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+type Map<K, V> = Arc<RwLock<HashMap<K, V>>>;
+
+// Changelogs are only looked up for mods with updates, so they're loaded from disk as needed
+#[derive(Clone)]
+pub struct ChangelogMap {
+    config: Arc<Config>,
+    logger: Logger,
+    map: Map<(String, u32), Option<Arc<Changelogs>>>,
+}
+
+impl ChangelogMap {
+    pub fn new(config: Arc<Config>, logger: Logger) -> Self {
+        Self {
+            config,
+            logger,
+            map: Default::default(),
+        }
+    }
+
+    pub async fn get(&self, game: &str, mod_id: u32) -> Option<Arc<Changelogs>> {
+        let mut lock = self.map.write().await;
+        if let Some(changelogs) = lock.get(&(game.to_string(), mod_id)) {
+            return changelogs.clone();
+        }
+        let path: PathBuf = DataPath::Changelogs(&self.config, game, mod_id).into();
+        let changelogs = Changelogs::load(&path).await.ok().map(|mut changelogs| {
+            changelogs.fetched = time::modified(&path.with_extension("json.zst")).unwrap_or_default();
+            Arc::new(changelogs)
+        });
+        // Negative results are cached too
+        lock.insert((game.to_string(), mod_id), changelogs.clone());
+        changelogs
+    }
+
+    pub async fn insert(&self, game: &str, mod_id: u32, changelogs: Changelogs) -> Arc<Changelogs> {
+        if let Err(e) = changelogs.save_compressed(DataPath::Changelogs(&self.config, game, mod_id)).await {
+            self.logger.log(format!("Failed to save changelogs of mod {mod_id}: {e}"));
+        }
+        let changelogs = Arc::new(changelogs);
+        self.map.write().await.insert((game.to_string(), mod_id), Some(changelogs.clone()));
+        changelogs
+    }
+}
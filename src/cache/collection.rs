@@ -0,0 +1,43 @@
+use super::Cacheable;
+use crate::api::CollectionRevision;
+use crate::config::DataPath;
+use crate::{Config, Logger};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/* The collection revision that was opened most recently. It's stored per profile, so the files that are still missing
+ * can be downloaded after a restart. */
+#[derive(Clone)]
+pub struct Collection {
+    config: Arc<Config>,
+    logger: Logger,
+    revision: Arc<RwLock<Option<Arc<CollectionRevision>>>>,
+    pub has_changed: Arc<AtomicBool>,
+}
+
+impl Collection {
+    pub async fn new(config: Arc<Config>, logger: Logger) -> Self {
+        let revision = CollectionRevision::load(DataPath::Collection(&config)).await.ok().map(Arc::new);
+        Self {
+            config,
+            logger,
+            revision: Arc::new(RwLock::new(revision)),
+            has_changed: Arc::new(true.into()),
+        }
+    }
+
+    pub async fn get(&self) -> Option<Arc<CollectionRevision>> {
+        self.revision.read().await.clone()
+    }
+
+    pub async fn set(&self, revision: CollectionRevision) -> Arc<CollectionRevision> {
+        if let Err(e) = revision.save(DataPath::Collection(&self.config)).await {
+            self.logger.log(format!("Unable to save collection {}: {e}", revision.collection.name));
+        }
+        let revision = Arc::new(revision);
+        *self.revision.write().await = Some(revision.clone());
+        self.has_changed.store(true, Ordering::Relaxed);
+        revision
+    }
+}
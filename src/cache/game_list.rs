@@ -0,0 +1,80 @@
+use super::Cacheable;
+use crate::api::{GameInfo, Games};
+use crate::config::DataPath;
+use crate::util::time;
+use crate::{Config, Logger};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/* The games on the Nexus, keyed by domain name. Games missing from the list, for example because it hasn't been
+ * fetched yet, are looked up from their own game info file if one exists. */
+#[derive(Clone)]
+pub struct GameList {
+    config: Arc<Config>,
+    logger: Logger,
+    inner: Arc<RwLock<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    games: HashMap<String, Arc<GameInfo>>,
+    // Time of the last update of the full list, zero if it's never been fetched
+    updated: u64,
+}
+
+impl GameList {
+    pub async fn new(config: Arc<Config>, logger: Logger) -> Self {
+        let mut inner = Inner::default();
+        let path: PathBuf = DataPath::Games(&config).into();
+        if let Ok(games) = Games::load(&path).await {
+            inner.games = games.games.into_iter().map(|gi| (gi.domain_name.clone(), Arc::new(gi))).collect();
+            inner.updated = time::modified(&path.with_extension("json.zst")).unwrap_or_default();
+        }
+        Self {
+            config,
+            logger,
+            inner: Arc::new(RwLock::new(inner)),
+        }
+    }
+
+    pub async fn replace(&self, games: Games) {
+        if let Err(e) = games.save_compressed(DataPath::Games(&self.config)).await {
+            self.logger.log(format!("Unable to save list of games: {e}"));
+        }
+        let mut inner = self.inner.write().await;
+        inner.games = games.games.into_iter().map(|gi| (gi.domain_name.clone(), Arc::new(gi))).collect();
+        inner.updated = time::now();
+    }
+
+    pub async fn get(&self, game: &str) -> Option<Arc<GameInfo>> {
+        if let Some(gi) = self.inner.read().await.games.get(game) {
+            return Some(gi.clone());
+        }
+        let gi = Arc::new(GameInfo::load(DataPath::GameInfo(&self.config, game)).await.ok()?);
+        self.inner.write().await.games.insert(game.to_string(), gi.clone());
+        Some(gi)
+    }
+
+    // None if the list hasn't been fetched, in which case we can't tell
+    pub async fn contains(&self, game: &str) -> Option<bool> {
+        let inner = self.inner.read().await;
+        match inner.updated {
+            0 => None,
+            _ => Some(inner.games.contains_key(game)),
+        }
+    }
+
+    // Seconds since the list was fetched
+    pub async fn age(&self) -> Option<u64> {
+        match self.inner.read().await.updated {
+            0 => None,
+            updated => Some(time::now().saturating_sub(updated)),
+        }
+    }
+
+    pub async fn category_name(&self, game: &str, category_id: u32) -> Option<String> {
+        self.get(game).await?.category_name(category_id).map(|name| name.to_string())
+    }
+}
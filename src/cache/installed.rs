@@ -1,3 +1,4 @@
+use crate::api::ModInfo;
 use crate::cache::{Cacheable, MetadataIndex};
 use crate::config::DataPath;
 use crate::extract::installed_mod::*;
@@ -65,11 +66,54 @@ impl Installed {
         self.mods.read().await.get_key_value(name).map(|(k, v)| (k.clone(), v.clone()))
     }
 
+    /* The directory a file of the mod would be installed into as an upgrade: the one whose installed file the file
+     * replaces in the update chain, otherwise the first directory in the load order that has a file of the mod. */
+    pub async fn find_by_mod(&self, game: &str, mod_id: u32, file_id: u64) -> Option<String> {
+        let dirs: Vec<(String, u64)> = self
+            .mods
+            .read()
+            .await
+            .iter()
+            .filter_map(|(dir_name, mod_dir)| match mod_dir {
+                ModDirectory::Nexus(im) if im.game == game && im.mod_id == mod_id => {
+                    Some((dir_name.clone(), im.file_id))
+                }
+                _ => None,
+            })
+            .collect();
+        for (dir_name, installed_id) in &dirs {
+            if self.metadata_index.is_replaced_by(game, mod_id, *installed_id, file_id).await {
+                return Some(dir_name.clone());
+            }
+        }
+        dirs.into_iter().next().map(|(dir_name, _)| dir_name)
+    }
+
+    // The cached mod info of the mod the installed file belongs to
+    pub async fn mod_info(&self, file_id: u64) -> Option<Arc<ModInfo>> {
+        self.metadata_index.get_by_file_id(&file_id).await?.mod_info().await
+    }
+
     pub async fn add(&self, dir_name: String, md: ModDirectory) {
         if let ModDirectory::Nexus(im) = &md {
             self.metadata_index.add_installed(dir_name.clone().clone(), im.file_id, im.clone()).await;
         }
-        self.mods.write().await.insert(dir_name, md);
+        let new_file_id = match &md {
+            ModDirectory::Nexus(im) => Some(im.file_id),
+            _ => None,
+        };
+        // Inserting an existing key keeps its position, so a reinstalled directory stays where it was in the load order
+        let replaced = self.mods.write().await.insert(dir_name.clone(), md);
+        if let Some(ModDirectory::Nexus(old)) = replaced {
+            if Some(old.file_id) != new_file_id {
+                if let Some(mfd) = self.metadata_index.get_by_file_id(&old.file_id).await {
+                    if mfd.remove_installed(&dir_name).await {
+                        self.archives_has_changed.store(true, Ordering::Relaxed);
+                    }
+                    self.metadata_index.remove_if_unreferenced(&mfd.file_id).await;
+                }
+            }
+        }
         self.has_changed.store(true, Ordering::Relaxed);
         self.save_load_order().await;
     }
@@ -0,0 +1,55 @@
+use super::Cacheable;
+use crate::config::DataPath;
+use crate::{Config, Logger};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/* The time of the last successful update check of each game. The Nexus lists the mods of a game that have been updated
+ * within the past month, so games checked more recently than that only need the mods on that list checked.
+ * Older versions stored one timestamp per profile, which can't tell which games were checked, so it isn't read. */
+#[derive(Clone)]
+pub struct LastUpdateCheck {
+    config: Arc<Config>,
+    logger: Logger,
+    times: Arc<RwLock<Timestamps>>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+struct Timestamps(HashMap<String, u64>); // key: game domain
+
+impl Cacheable for Timestamps {}
+
+impl LastUpdateCheck {
+    pub async fn new(config: Arc<Config>, logger: Logger) -> Self {
+        let times = Timestamps::load(DataPath::LastUpdateCheck(&config)).await.unwrap_or_default();
+        Self {
+            config,
+            logger,
+            times: Arc::new(RwLock::new(times)),
+        }
+    }
+
+    // 0 if the game has never been checked
+    pub async fn get(&self, game: &str) -> u64 {
+        self.times.read().await.0.get(game).copied().unwrap_or_default()
+    }
+
+    // The time of the most recent check of any game
+    pub async fn latest(&self) -> u64 {
+        self.times.read().await.0.values().max().copied().unwrap_or_default()
+    }
+
+    pub async fn set(&self, game: &str, time: u64) {
+        let times = {
+            let mut lock = self.times.write().await;
+            lock.0.insert(game.to_string(), time);
+            Timestamps(lock.0.clone())
+        };
+        if let Err(e) = times.save(DataPath::LastUpdateCheck(&self.config)).await {
+            self.logger.log(format!("Failed to save the time of the last update check: {e}"));
+        }
+    }
+}
@@ -72,6 +72,9 @@ impl MetadataIndex {
                     mfd.set_file_details(fd).await;
                 }
             }
+            if let Some(file_list) = self.file_lists.get(mfd.game.to_owned(), mfd.mod_id).await {
+                mfd.set_replacement(file_list.newest_replacement(mfd.file_id)).await;
+            }
         }
         {
             let mut modinfo_lock = mfd.mod_info.write().await;
@@ -124,6 +127,14 @@ impl MetadataIndex {
         mfd
     }
 
+    // Whether the old file of the mod has been replaced by the new one, according to the mod's cached file list
+    pub async fn is_replaced_by(&self, game: &str, mod_id: u32, old_file_id: u64, new_file_id: u64) -> bool {
+        match self.file_lists.get(game, mod_id).await {
+            Some(file_list) => file_list.is_replaced_by(old_file_id, new_file_id),
+            None => false,
+        }
+    }
+
     pub async fn get_by_file_id(&self, file_id: &u64) -> Option<Arc<ModFileMetadata>> {
         self.by_file_id.read().await.get(file_id).cloned()
     }
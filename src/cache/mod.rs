@@ -1,44 +1,57 @@
+mod account;
 mod archive_files;
 pub mod cache_error;
 mod cacheable;
+mod changelog_map;
+mod collection;
 mod file_lists;
+mod game_list;
 mod installed;
+mod last_update_check;
 mod md5result_map;
 mod metadata_index;
 mod modfile_metadata;
 mod modinfo_map;
+mod tracked;
 
+pub use account::Account;
 pub use archive_files::*;
 pub use cache_error::CacheError;
-pub use cacheable::Cacheable;
+pub use cacheable::{Cacheable, Validators};
+pub use changelog_map::ChangelogMap;
+pub use collection::Collection;
 pub use file_lists::*;
+pub use game_list::GameList;
 pub use installed::*;
+pub use last_update_check::LastUpdateCheck;
 pub use md5result_map::*;
 pub use metadata_index::*;
 pub use modfile_metadata::ModFileMetadata;
 pub use modinfo_map::*;
+pub use tracked::Tracked;
 
 use crate::api::{DownloadLink, FileList, Md5Result, ModInfo};
 use crate::config::{Config, DataPath};
 use crate::Logger;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use tokio::fs;
-use tokio::fs::File;
-use tokio::io;
-use tokio::io::AsyncWriteExt;
 
 #[derive(Clone)]
 pub struct Cache {
     config: Arc<Config>,
     logger: Logger,
+    pub account: Account,
     pub archives: ArchiveFiles,
+    pub changelogs: ChangelogMap,
+    pub collection: Collection,
     pub file_lists: FileLists,
+    pub games: GameList,
     pub metadata_index: MetadataIndex,
     pub md5result: Md5ResultMap,
     pub mod_info: ModInfoMap,
-    pub last_update_check: Arc<AtomicU64>,
+    pub last_update_check: LastUpdateCheck,
     pub installed: Installed,
+    pub tracked: Tracked,
 }
 
 impl Cache {
@@ -63,18 +76,28 @@ impl Cache {
             archives_has_changed,
         )
         .await;
-        let last_update_check = Arc::new(try_read_last_updated(&config).into());
+        let last_update_check = LastUpdateCheck::new(config.clone(), logger.clone()).await;
+        let tracked = Tracked::new(config.clone(), logger.clone()).await;
+        let account = Account::new(config.clone(), logger.clone()).await;
+        let games = GameList::new(config.clone(), logger.clone()).await;
+        let changelogs = ChangelogMap::new(config.clone(), logger.clone());
+        let collection = Collection::new(config.clone(), logger.clone()).await;
 
         Ok(Self {
+            account,
             archives,
+            changelogs,
+            collection,
             installed,
             config,
             logger,
             file_lists,
+            games,
             metadata_index,
             md5result,
             mod_info,
             last_update_check,
+            tracked,
         })
     }
 
@@ -149,11 +172,9 @@ impl Cache {
             self.logger.log(format!("Failed to save ModInfo to disk: {e}"));
         }
         if let Some(files) = self.metadata_index.get_modfiles(&mi.domain_name, &mi.mod_id).await {
+            // Replace any older mod info, since it might have a stale endorsement status
             for mfd in files {
-                let mut mi_lock = mfd.mod_info.write().await;
-                if mi_lock.is_none() {
-                    *mi_lock = Some(mi.clone());
-                }
+                *mfd.mod_info.write().await = Some(mi.clone());
             }
         }
         self.mod_info.insert(mi.clone()).await;
@@ -169,25 +190,6 @@ impl Cache {
         }
         self.md5result.insert(game.clone(), res.clone()).await;
     }
-
-    pub async fn save_last_updated(&self, time: u64) -> Result<(), io::Error> {
-        self.last_update_check.store(time, Ordering::Relaxed);
-        let mut path = self.config.cache_for_profile();
-        fs::create_dir_all(&path).await?;
-        path.push("last_updated");
-        let mut file = File::create(path).await?;
-        file.write_all(format!("{}", time).as_bytes()).await
-    }
-}
-
-// Loads timestamp from $XDG_CACHE_DIR/dmodman/$profile/last_updated
-fn try_read_last_updated(config: &Config) -> u64 {
-    let mut path = config.cache_for_profile();
-    path.push("last_updated");
-    match std::fs::read_to_string(path) {
-        Ok(contents) => contents.parse::<u64>().unwrap_or_default(),
-        Err(_) => 0,
-    }
 }
 
 #[cfg(test)]
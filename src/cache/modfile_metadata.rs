@@ -1,5 +1,5 @@
 use crate::api::downloads::FileInfo;
-use crate::api::{FileDetails, ModInfo, UpdateStatus, UpdateStatusWrapper};
+use crate::api::{FileDetails, ModInfo, Replacement, UpdateStatus, UpdateStatusWrapper};
 use crate::cache::{ArchiveFile, ArchiveStatus, Cacheable};
 use crate::config::{Config, DataPath};
 use crate::extract::{InstalledMod, ModDirectory};
@@ -17,6 +17,7 @@ pub struct ModFileMetadata {
     installed_mods: Arc<RwLock<HashMap<String, Arc<InstalledMod>>>>,
     mod_archives: Arc<RwLock<HashMap<String, Arc<ArchiveFile>>>>,
     pub mod_info: Arc<RwLock<Option<Arc<ModInfo>>>>,
+    replacement: Arc<RwLock<Option<Replacement>>>,
     pub update_status: UpdateStatusWrapper,
 }
 
@@ -41,6 +42,7 @@ impl ModFileMetadata {
             mod_archives: Default::default(),
             file_details: Default::default(),
             mod_info: Default::default(),
+            replacement: Default::default(),
             update_status: Default::default(),
             installed_mods: Default::default(),
         }
@@ -118,6 +120,11 @@ impl ModFileMetadata {
         }
     }
 
+    // The newest file in this file's update chain, if it has been replaced
+    pub async fn replacement(&self) -> Option<Replacement> {
+        self.replacement.read().await.clone()
+    }
+
     /// Returns bool saying whether archives need refresh
     pub async fn remove_installed(&self, dir_name: &str) -> bool {
         let not_installed = {
@@ -141,6 +148,10 @@ impl ModFileMetadata {
         *self.file_details.write().await = Some(fd);
     }
 
+    pub async fn set_replacement(&self, replacement: Option<Replacement>) {
+        *self.replacement.write().await = replacement;
+    }
+
     pub async fn uploaded_timestamp(&self) -> Option<u64> {
         self.file_details.read().await.as_ref().map(|fd| fd.uploaded_timestamp)
     }
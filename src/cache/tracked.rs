@@ -0,0 +1,75 @@
+use super::Cacheable;
+use crate::api::{TrackedMod, TrackedMods};
+use crate::config::DataPath;
+use crate::{Config, Logger};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/* The mods the user tracks on the Nexus. Tracking is tied to the account rather than a profile, so the list is shared
+ * by all profiles. It's stored locally so the tracked view doesn't need to wait for the API on startup. */
+#[derive(Clone)]
+pub struct Tracked {
+    config: Arc<Config>,
+    logger: Logger,
+    pub mods: Arc<RwLock<BTreeSet<TrackedMod>>>,
+    pub has_changed: Arc<AtomicBool>,
+}
+
+impl Tracked {
+    pub async fn new(config: Arc<Config>, logger: Logger) -> Self {
+        let mods = TrackedMods::load(DataPath::TrackedMods(&config)).await.unwrap_or_default();
+        Self {
+            config,
+            logger,
+            mods: Arc::new(RwLock::new(mods.mods.into_iter().collect())),
+            has_changed: Arc::new(true.into()),
+        }
+    }
+
+    pub async fn contains(&self, game: &str, mod_id: u32) -> bool {
+        self.mods.read().await.contains(&TrackedMod {
+            domain_name: game.to_string(),
+            mod_id,
+        })
+    }
+
+    pub async fn insert(&self, game: &str, mod_id: u32) {
+        let inserted = self.mods.write().await.insert(TrackedMod {
+            domain_name: game.to_string(),
+            mod_id,
+        });
+        if inserted {
+            self.has_changed.store(true, Ordering::Relaxed);
+            self.save().await;
+        }
+    }
+
+    pub async fn remove(&self, game: &str, mod_id: u32) {
+        let removed = self.mods.write().await.remove(&TrackedMod {
+            domain_name: game.to_string(),
+            mod_id,
+        });
+        if removed {
+            self.has_changed.store(true, Ordering::Relaxed);
+            self.save().await;
+        }
+    }
+
+    // Replaces the local list with the one from the Nexus
+    pub async fn replace(&self, tracked: TrackedMods) {
+        *self.mods.write().await = tracked.mods.into_iter().collect();
+        self.has_changed.store(true, Ordering::Relaxed);
+        self.save().await;
+    }
+
+    async fn save(&self) {
+        let tracked = TrackedMods {
+            mods: self.mods.read().await.iter().cloned().collect(),
+        };
+        if let Err(e) = tracked.save(DataPath::TrackedMods(&self.config)).await {
+            self.logger.log(format!("Unable to save tracked mods: {e}"));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::api::{Client, Query, UpdateChecker, UpdateStatus};
+use crate::cache::Cache;
+use crate::{Config, Logger};
+use serde::Serialize;
+use std::error::Error;
+use std::sync::Arc;
+
+/* `dmodman check-updates` checks the downloaded files for updates and prints a report of them, either as a table or
+ * with --json as JSON. The exit code tells the result, so that the check can be used in scripts. */
+
+const EXIT_OUT_OF_DATE: i32 = 1;
+// Some mods couldn't be checked, so the report can show outdated statuses for them
+const EXIT_INCOMPLETE: i32 = 2;
+
+const HEADERS: [&str; 6] = ["GAME", "MOD ID", "FILE ID", "VERSION", "STATUS", "NAME"];
+
+#[derive(Debug, Serialize)]
+struct FileReport {
+    game: String,
+    mod_id: u32,
+    file_id: u64,
+    version: Option<String>,
+    update_status: String,
+    name: Option<String>,
+}
+
+// Returns the exit code, which is 0 if every file could be checked and is up to date
+pub async fn check_updates(config: Arc<Config>, logger: Logger, json: bool) -> Result<i32, Box<dyn Error>> {
+    let cache = Cache::new(config.clone(), logger.clone()).await?;
+    let client = Client::new(&config).await?;
+    let query = Query::new(cache.clone(), client.clone(), config.clone(), logger.clone());
+    let updater = UpdateChecker::new(cache.clone(), client, config, logger.clone(), query);
+    let mut failed_games: Vec<String> = updater.check_all().await.into_iter().collect();
+
+    let (reports, out_of_date) = file_reports(&cache).await;
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&reports)?),
+        false => print!("{}", format_table(&reports)),
+    }
+    if !failed_games.is_empty() {
+        failed_games.sort();
+        logger.log(format!("The check was incomplete for {}.", failed_games.join(", ")));
+        return Ok(EXIT_INCOMPLETE);
+    }
+    Ok(if out_of_date { EXIT_OUT_OF_DATE } else { 0 })
+}
+
+async fn file_reports(cache: &Cache) -> (Vec<FileReport>, bool) {
+    let mut reports = vec![];
+    let mut out_of_date = false;
+    for mods in cache.metadata_index.by_game_and_mod_sorted.read().await.values() {
+        for mfd in mods.values().flatten() {
+            let status = mfd.update_status.to_enum();
+            out_of_date |= matches!(status, UpdateStatus::OutOfDate(_));
+            let file_details = mfd.file_details().await;
+            reports.push(FileReport {
+                game: mfd.game.clone(),
+                mod_id: mfd.mod_id,
+                file_id: mfd.file_id,
+                version: file_details.as_ref().and_then(|fd| fd.version.clone()),
+                update_status: status.to_string(),
+                name: file_details.map(|fd| fd.name.clone()),
+            });
+        }
+    }
+    reports.sort_by(|a, b| (&a.game, a.mod_id, a.file_id).cmp(&(&b.game, b.mod_id, b.file_id)));
+    (reports, out_of_date)
+}
+
+fn format_table(reports: &[FileReport]) -> String {
+    let rows: Vec<[String; 6]> = reports
+        .iter()
+        .map(|r| {
+            [
+                r.game.clone(),
+                r.mod_id.to_string(),
+                r.file_id.to_string(),
+                r.version.clone().unwrap_or_default(),
+                r.update_status.clone(),
+                r.name.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADERS.map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(HEADERS.map(String::from)).chain(rows) {
+        let cells: Vec<String> = row.iter().zip(widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+        table.push_str(cells.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_reports, format_table};
+    use crate::api::mock_server::MockEnv;
+
+    #[tokio::test]
+    async fn report_files() {
+        let env = MockEnv::with_archives(&[
+            "GH TR - PT Meshes-46599-1-01-1556986716.7z",
+            "Graphic Herbalism MWSE - OpenMW-46599-1-03-1556986083.7z",
+        ])
+        .await;
+
+        let (reports, out_of_date) = file_reports(&env.cache).await;
+        assert!(out_of_date);
+        assert_eq!(2, reports.len());
+        assert_eq!((1000014314, "OutOfDate"), (reports[0].file_id, reports[0].update_status.as_str()));
+        assert_eq!((1000014318, "UpToDate"), (reports[1].file_id, reports[1].update_status.as_str()));
+
+        let table = format_table(&reports);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("GAME       MOD ID  FILE ID     VERSION"));
+        assert!(lines[1].starts_with("morrowind  46599   1000014314"));
+        assert!(lines[1].contains("OutOfDate"));
+    }
+}
@@ -13,6 +13,14 @@ pub enum ConfigError {
     ShellExpand {
         source: shellexpand::LookupError<std::env::VarError>,
     },
+    InvalidEndpoint {
+        name: &'static str,
+        reason: String,
+    },
+    InvalidNetwork {
+        name: &'static str,
+        reason: String,
+    },
 }
 
 impl Error for ConfigError {
@@ -21,6 +29,8 @@ impl Error for ConfigError {
             ConfigError::IO { ref source } => Some(source),
             ConfigError::Deserialization { ref source } => Some(source),
             ConfigError::ShellExpand { ref source } => Some(source),
+            ConfigError::InvalidEndpoint { .. } => None,
+            ConfigError::InvalidNetwork { .. } => None,
         }
     }
 }
@@ -31,6 +41,10 @@ impl fmt::Display for ConfigError {
             ConfigError::IO { source } => source.fmt(f),
             ConfigError::Deserialization { source } => source.fmt(f),
             ConfigError::ShellExpand { source } => source.fmt(f),
+            ConfigError::InvalidEndpoint { name, reason } => write!(f, "Invalid {name} endpoint in config: {reason}"),
+            ConfigError::InvalidNetwork { name, reason } => {
+                write!(f, "Invalid network setting {name} in config: {reason}")
+            }
         }
     }
 }
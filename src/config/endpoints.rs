@@ -0,0 +1,128 @@
+use super::ConfigError;
+use crate::Logger;
+use serde::Deserialize;
+use url::Url;
+
+pub const API_URL: &str = "https://api.nexusmods.com/v1/";
+pub const GRAPHQL_URL: &str = "https://api-router.nexusmods.com/graphql";
+pub const SEARCH_URL: &str = "https://search.nexusmods.com/mods";
+pub const SSO_URL: &str = "wss://sso.nexusmods.com";
+pub const SSO_PAGE_URL: &str = "https://www.nexusmods.com/sso";
+
+/* The [endpoints] table of the config. These don't need to be changed unless dmodman should talk to something other
+ * than the Nexus, such as a caching proxy or a local mock server. */
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct EndpointsBuilder {
+    api: Option<String>,
+    graphql: Option<String>,
+    search: Option<String>,
+    sso: Option<String>,
+    sso_page: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Endpoints {
+    pub api: Url,
+    pub graphql: Url, // the v2 API, needed for collections
+    pub search: Url,
+    pub sso: Url,      // websocket used to request an API key
+    pub sso_page: Url, // page where the user authorizes the API key request
+}
+
+impl EndpointsBuilder {
+    #[allow(dead_code)]
+    pub fn graphql<S: Into<String>>(mut self, url: S) -> Self {
+        self.graphql = Some(url.into());
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn search<S: Into<String>>(mut self, url: S) -> Self {
+        self.search = Some(url.into());
+        self
+    }
+
+    pub fn build(self, logger: &Logger) -> Result<Endpoints, ConfigError> {
+        const HTTP: &[&str] = &["http", "https"];
+        const WEBSOCKET: &[&str] = &["ws", "wss"];
+
+        let mut api = parse_endpoint("api", self.api, API_URL, HTTP, logger)?;
+        /* Endpoints are joined to the API URL, which would replace the last path segment if the URL doesn't end with a
+         * slash. */
+        if !api.path().ends_with('/') {
+            api.set_path(&format!("{}/", api.path()));
+        }
+
+        Ok(Endpoints {
+            api,
+            graphql: parse_endpoint("graphql", self.graphql, GRAPHQL_URL, HTTP, logger)?,
+            search: parse_endpoint("search", self.search, SEARCH_URL, HTTP, logger)?,
+            sso: parse_endpoint("sso", self.sso, SSO_URL, WEBSOCKET, logger)?,
+            sso_page: parse_endpoint("sso_page", self.sso_page, SSO_PAGE_URL, HTTP, logger)?,
+        })
+    }
+}
+
+fn parse_endpoint(
+    name: &'static str,
+    value: Option<String>,
+    default: &str,
+    schemes: &[&str],
+    logger: &Logger,
+) -> Result<Url, ConfigError> {
+    let Some(value) = value else {
+        return Ok(Url::parse(default).unwrap());
+    };
+    let url = Url::parse(&value).map_err(|e| ConfigError::InvalidEndpoint {
+        name,
+        reason: format!("\"{value}\" is not a valid URL: {e}"),
+    })?;
+    if !schemes.contains(&url.scheme()) {
+        return Err(ConfigError::InvalidEndpoint {
+            name,
+            reason: format!("expected URL scheme to be one of {}, got \"{}\"", schemes.join(", "), url.scheme()),
+        });
+    }
+    if url != Url::parse(default).unwrap() {
+        logger.log(format!("Using non-default {name} endpoint: {url}"));
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EndpointsBuilder;
+    use crate::config::ConfigError;
+    use crate::Logger;
+
+    #[test]
+    fn default_endpoints() -> Result<(), ConfigError> {
+        let endpoints = EndpointsBuilder::default().build(&Logger::default())?;
+        assert_eq!("https://api.nexusmods.com/v1/", endpoints.api.as_str());
+        assert_eq!("wss://sso.nexusmods.com/", endpoints.sso.as_str());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_endpoints() {
+        let logger = Logger::default();
+        let builder = EndpointsBuilder {
+            api: Some("http://127.0.0.1:8080/v1".to_string()),
+            ..Default::default()
+        };
+        let endpoints = builder.build(&logger).unwrap();
+        assert_eq!("http://127.0.0.1:8080/v1/", endpoints.api.as_str());
+
+        let builder = EndpointsBuilder {
+            sso: Some("https://sso.nexusmods.com".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(builder.build(&logger), Err(ConfigError::InvalidEndpoint { name: "sso", .. })));
+
+        let builder = EndpointsBuilder {
+            search: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(builder.build(&logger), Err(ConfigError::InvalidEndpoint { name: "search", .. })));
+    }
+}
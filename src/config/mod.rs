@@ -1,10 +1,16 @@
 pub mod config_error;
+pub mod endpoints;
+pub mod network;
 pub mod paths;
 
 pub use config_error::ConfigError;
+pub use endpoints::Endpoints;
+pub use network::Network;
 pub use paths::DataPath;
 
 use super::Logger;
+use endpoints::EndpointsBuilder;
+use network::NetworkBuilder;
 use crate::util;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -12,6 +18,7 @@ use std::env;
 use std::io::prelude::Write;
 use std::io::Read;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{fs, fs::File};
 
 /* The ConfigBuilder is loaded based on the config file, or initialized with empty values. It's used for deserializing
@@ -34,6 +41,22 @@ pub struct ConfigBuilder {
     #[serde(alias = "global_install_dir")]
     install_dir: Option<PathBuf>,
     profiles: HashMap<String, Profile>,
+    // Set from the active profile
+    #[serde(skip)]
+    game: Option<String>,
+    rate_limit_reserve: Option<u16>,
+    track_downloads: Option<bool>,
+    offline: Option<bool>,
+    update_interval: Option<u64>, // hours
+    update_command: Option<Vec<String>>,
+    update_concurrency: Option<usize>,
+    #[serde(default)]
+    endpoints: EndpointsBuilder,
+    #[serde(default)]
+    network: NetworkBuilder,
+    // Only settable by tests, which need somewhere to write the metadata of their fake API responses
+    #[serde(skip)]
+    data_dir: Option<PathBuf>,
     #[serde(skip)]
     logger: Logger,
 }
@@ -42,9 +65,12 @@ pub struct ConfigBuilder {
 struct Profile {
     download_dir: Option<PathBuf>,
     install_dir: Option<PathBuf>,
+    game: Option<String>,
 }
 
 const DEFAULT_PROFILE_NAME: &str = "default";
+const DEFAULT_RATE_LIMIT_RESERVE: u16 = 10;
+const DEFAULT_UPDATE_CONCURRENCY: usize = 4;
 
 impl ConfigBuilder {
     pub fn load(logger: Logger) -> Result<Self, ConfigError> {
@@ -68,6 +94,9 @@ impl ConfigBuilder {
                 if let Some(ins_dir) = &profile.install_dir {
                     self.install_dir = Some(ins_dir.to_owned());
                 };
+                if let Some(game) = &profile.game {
+                    self.game = Some(game.to_owned());
+                };
             }
         }
     }
@@ -98,6 +127,36 @@ impl ConfigBuilder {
         self
     }
 
+    #[allow(dead_code)]
+    pub fn track_downloads(mut self, track: bool) -> Self {
+        self.track_downloads = Some(track);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = Some(offline);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn graphql_endpoint<S: Into<String>>(mut self, url: S) -> Self {
+        self.endpoints = self.endpoints.graphql(url);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn search_endpoint<S: Into<String>>(mut self, url: S) -> Self {
+        self.endpoints = self.endpoints.search(url);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn data_dir<S: Into<String>>(mut self, dir: S) -> Self {
+        self.data_dir = Some(PathBuf::from(dir.into()));
+        self
+    }
+
     pub fn build(mut self) -> Result<Config, ConfigError> {
         // API key can be stored in the config or a separate file (default). Config takes precedence.
         if self.apikey.is_none() {
@@ -186,6 +245,18 @@ pub struct Config {
     profile: String,
     download_dir: PathBuf,
     install_dir: PathBuf,
+    pub game: Option<String>, // the game domain of the active profile
+    pub rate_limit_reserve: u16,
+    pub track_downloads: bool,
+    pub offline: bool, // whether dmodman starts in offline mode
+    // How often the daemon checks updates, and what it runs with a summary of files that have become out of date
+    pub update_interval: Option<Duration>,
+    pub update_command: Vec<String>,
+    // How many mods are checked for updates at the same time
+    pub update_concurrency: usize,
+    pub endpoints: Endpoints,
+    pub network: Network,
+    data_dir: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -222,16 +293,34 @@ impl Config {
             }
         };
 
+        let endpoints = config.endpoints.build(&logger)?;
+        let network = config.network.build()?;
+
         Ok(Self {
             apikey: config.apikey,
             profile: config.profile.unwrap_or("default".to_string()),
             download_dir,
             install_dir,
+            game: config.game,
+            rate_limit_reserve: config.rate_limit_reserve.unwrap_or(DEFAULT_RATE_LIMIT_RESERVE),
+            track_downloads: config.track_downloads.unwrap_or_default(),
+            offline: config.offline.unwrap_or_default(),
+            // An interval of 0 would check updates continuously, so it disables the checks instead
+            update_interval: config.update_interval.filter(|h| *h > 0).map(|h| Duration::from_secs(h * 60 * 60)),
+            update_command: config.update_command.unwrap_or_default(),
+            update_concurrency: config.update_concurrency.filter(|n| *n > 0).unwrap_or(DEFAULT_UPDATE_CONCURRENCY),
+            endpoints,
+            network,
+            data_dir: config.data_dir,
         })
     }
 
     pub fn cache_for_profile(&self) -> PathBuf {
-        let mut path = dirs::cache_dir().unwrap();
+        // Tests that set their own data dir shouldn't write into the user's cache either
+        let mut path = match &self.data_dir {
+            Some(dir) => dir.join("cache"),
+            None => dirs::cache_dir().unwrap(),
+        };
         path.push(env!("CARGO_CRATE_NAME"));
         path.push(&self.profile);
         path
@@ -239,7 +328,9 @@ impl Config {
 
     pub fn data_dir(&self) -> PathBuf {
         let mut path;
-        if cfg!(test) {
+        if let Some(dir) = &self.data_dir {
+            path = dir.clone();
+        } else if cfg!(test) {
             path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data");
         } else {
             path = dirs::data_local_dir().unwrap();
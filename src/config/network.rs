@@ -0,0 +1,148 @@
+use super::ConfigError;
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use url::Url;
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_READ_TIMEOUT_SECS: u64 = 60;
+
+/* The [network] table of the config. The settings apply to API requests, file downloads and the SSO websocket alike,
+ * so that dmodman works behind proxies that intercept TLS. */
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct NetworkBuilder {
+    proxy: Option<String>,
+    #[serde(default)]
+    ca_certs: Vec<PathBuf>,
+    connect_timeout: Option<u64>,
+    read_timeout: Option<u64>,
+    user_agent_suffix: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Network {
+    pub proxy: Option<Url>,
+    pub ca_certs: Vec<Vec<u8>>, // PEM encoded, one bundle per configured file
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    pub user_agent_suffix: Option<String>,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        NetworkBuilder::default().build().unwrap()
+    }
+}
+
+impl NetworkBuilder {
+    pub fn build(self) -> Result<Network, ConfigError> {
+        const PROXY: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+        let proxy = match self.proxy {
+            Some(value) => {
+                let url = Url::parse(&value).map_err(|e| ConfigError::InvalidNetwork {
+                    name: "proxy",
+                    reason: format!("\"{value}\" is not a valid URL: {e}"),
+                })?;
+                if !PROXY.contains(&url.scheme()) {
+                    return Err(ConfigError::InvalidNetwork {
+                        name: "proxy",
+                        reason: format!(
+                            "expected URL scheme to be one of {}, got \"{}\"",
+                            PROXY.join(", "),
+                            url.scheme()
+                        ),
+                    });
+                }
+                Some(url)
+            }
+            None => None,
+        };
+
+        let mut ca_certs = vec![];
+        for path in self.ca_certs {
+            let path = PathBuf::from(shellexpand::full(&path.to_string_lossy())?.to_string());
+            let pem = fs::read(&path).map_err(|e| ConfigError::InvalidNetwork {
+                name: "ca_certs",
+                reason: format!("unable to read {path:?}: {e}"),
+            })?;
+            // Checked here so that a bad bundle is reported on startup instead of failing every request
+            if reqwest::Certificate::from_pem_bundle(&pem).map_or(true, |certs| certs.is_empty()) {
+                return Err(ConfigError::InvalidNetwork {
+                    name: "ca_certs",
+                    reason: format!("{path:?} doesn't contain any PEM encoded certificates"),
+                });
+            }
+            ca_certs.push(pem);
+        }
+
+        // The suffix is sent in the User-Agent header, which only allows visible ASCII
+        let user_agent_suffix = self.user_agent_suffix.filter(|s| !s.is_empty());
+        if let Some(suffix) = &user_agent_suffix {
+            if !suffix.is_ascii() || HeaderValue::from_str(suffix).is_err() {
+                return Err(ConfigError::InvalidNetwork {
+                    name: "user_agent_suffix",
+                    reason: format!("{suffix:?} contains characters that aren't allowed in a header"),
+                });
+            }
+        }
+
+        Ok(Network {
+            proxy,
+            ca_certs,
+            connect_timeout: Duration::from_secs(self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS)),
+            read_timeout: Duration::from_secs(self.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT_SECS)),
+            user_agent_suffix,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NetworkBuilder;
+    use crate::config::ConfigError;
+    use std::time::Duration;
+
+    #[test]
+    fn validate_network() {
+        let network = NetworkBuilder {
+            proxy: Some("socks5h://127.0.0.1:1080".to_string()),
+            read_timeout: Some(5),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        assert_eq!("socks5h", network.proxy.unwrap().scheme());
+        assert_eq!(Duration::from_secs(5), network.read_timeout);
+
+        let builder = NetworkBuilder {
+            proxy: Some("ftp://127.0.0.1".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(builder.build(), Err(ConfigError::InvalidNetwork { name: "proxy", .. })));
+
+        let builder = NetworkBuilder {
+            ca_certs: vec!["/nonexistent/ca.pem".into()],
+            ..Default::default()
+        };
+        assert!(matches!(builder.build(), Err(ConfigError::InvalidNetwork { name: "ca_certs", .. })));
+
+        // Header values can't contain line breaks, and non-ASCII characters aren't portable
+        let builder = NetworkBuilder {
+            user_agent_suffix: Some("contact\nme".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            matches!(builder.build(), Err(ConfigError::InvalidNetwork { name, .. }) if name == "user_agent_suffix")
+        );
+        let builder = NetworkBuilder {
+            user_agent_suffix: Some("é".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            matches!(builder.build(), Err(ConfigError::InvalidNetwork { name, .. }) if name == "user_agent_suffix")
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use super::Config;
 use crate::api::downloads::DownloadInfo;
 
+pub const CHANGELOGS: &str = "changelogs";
 pub const DL_LINKS: &str = "download_links";
 pub const FILE_LISTS: &str = "file_lists";
 pub const MD5_RESULTS: &str = "md5_results";
@@ -11,18 +12,24 @@ pub const MOD_INFO: &str = "mod_info";
 #[allow(dead_code)]
 pub enum DataPath<'a> {
     // API formats
+    Changelogs(&'a Config, &'a str, u32),        // game, mod_id
     DownloadLink(&'a Config, &'a str, u32, u64), // game, mod_id, file_id
     FileList(&'a Config, &'a str, u32),          // game, mod_id
     Md5Results(&'a Config, &'a str, u64),        // game, file_id
     ModInfo(&'a Config, &'a str, u32),           // game, mod_id
     Updated(&'a Config, &'a str),                // game
+    TrackedMods(&'a Config),
+    Collection(&'a Config),
+    UserDetails(&'a Config),
 
     // Local formats
     ModDirMetadata(&'a Config, &'a String),
     ArchiveMetadata(&'a Config, &'a String),
     DownloadInfo(&'a Config, &'a DownloadInfo),
+    LastUpdateCheck(&'a Config),
 
     GameInfo(&'a Config, &'a str), // game
+    Games(&'a Config),
 
     // Old paths to be checked for backwards compatibility
     FileListCompat(&'a Config, &'a str, u32), // game, mod_id
@@ -36,6 +43,12 @@ impl From<DataPath<'_>> for PathBuf {
                 path = config.download_dir();
                 path.push(format!("{}.part.json", di.file_info.file_name));
             }
+            DataPath::Changelogs(config, game, mod_id) => {
+                path = config.metadata_dir();
+                path.push(game);
+                path.push(CHANGELOGS);
+                path.push(format!("{}.json", mod_id));
+            }
             DataPath::DownloadLink(config, game, mod_id, file_id) => {
                 path = config.metadata_for_profile();
                 path.push(game);
@@ -57,7 +70,12 @@ impl From<DataPath<'_>> for PathBuf {
             }
             DataPath::GameInfo(config, game) => {
                 path = config.metadata_dir();
-                path.push(format!("{}.json", game));
+                path.push(game);
+                path.push("game_info.json");
+            }
+            DataPath::Games(config) => {
+                path = config.metadata_dir();
+                path.push("games.json");
             }
             DataPath::ModDirMetadata(config, dir_name) => {
                 path = config.install_dir();
@@ -68,6 +86,10 @@ impl From<DataPath<'_>> for PathBuf {
                 path = config.download_dir();
                 path.push(format!("{}.json", file_name));
             }
+            DataPath::LastUpdateCheck(config) => {
+                path = config.cache_for_profile();
+                path.push("last_update_check.json");
+            }
             DataPath::Md5Results(config, game, file_id) => {
                 path = config.metadata_dir();
                 path.push(game);
@@ -85,6 +107,18 @@ impl From<DataPath<'_>> for PathBuf {
                 path.push(game);
                 path.push("updated.json");
             }
+            DataPath::Collection(config) => {
+                path = config.metadata_for_profile();
+                path.push("collection.json");
+            }
+            DataPath::TrackedMods(config) => {
+                path = config.data_dir();
+                path.push("tracked_mods.json");
+            }
+            DataPath::UserDetails(config) => {
+                path = config.data_dir();
+                path.push("user.json");
+            }
         }
         path
     }
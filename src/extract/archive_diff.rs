@@ -0,0 +1,169 @@
+use super::libarchive::{bindings, Archive, ArchiveError};
+use super::{normalize_path, InstallError};
+use crate::util;
+use md5::{Digest, Md5};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/* What installing an archive would change in an installed directory. Files are compared by their path and size, and
+ * files of the same size by their md5 sum. Paths are relative to the directory. */
+#[derive(Debug, Default)]
+pub struct ArchiveDiff {
+    pub added: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    // Only in the directory, such as configs created by the user or files left out of the new version
+    pub removed: Vec<PathBuf>,
+    pub unchanged: usize,
+}
+
+impl ArchiveDiff {
+    // One line per file that differs, prefixed with +, ~ or -
+    pub fn lines(&self) -> Vec<String> {
+        let added = self.added.iter().map(|p| format!("+ {}", p.display()));
+        let modified = self.modified.iter().map(|p| format!("~ {}", p.display()));
+        let removed = self.removed.iter().map(|p| format!("- {}", p.display()));
+        added.chain(modified).chain(removed).collect()
+    }
+}
+
+impl fmt::Display for ArchiveDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} added, {} modified, {} removed, {} unchanged.",
+            self.added.len(),
+            self.modified.len(),
+            self.removed.len(),
+            self.unchanged
+        )
+    }
+}
+
+// Reads the archive without extracting it
+pub async fn diff(archive_path: &Path, dir_path: &Path) -> Result<ArchiveDiff, InstallError> {
+    let mut installed = list_files(dir_path).await?;
+    let mut diff = ArchiveDiff::default();
+
+    let archive = Archive::open(archive_path.to_string_lossy().to_string()).await?;
+    while let Some(entry_res) = archive.next().await {
+        let entry = entry_res?;
+        if entry.is_dir().await {
+            continue;
+        }
+        let path = normalize_path(&entry.path().await);
+        match installed.remove(&path) {
+            None => diff.added.push(path),
+            Some(size) if size != entry.size().await => diff.modified.push(path),
+            Some(_) => {
+                if md5sum_entry(&archive).await? == util::md5sum(dir_path.join(&path)).await? {
+                    diff.unchanged += 1;
+                } else {
+                    diff.modified.push(path);
+                }
+            }
+        }
+    }
+    diff.removed = installed.into_keys().collect();
+
+    diff.added.sort();
+    diff.modified.sort();
+    diff.removed.sort();
+    Ok(diff)
+}
+
+// Sizes of the files in the directory and its subdirectories, keyed by their path relative to it
+async fn list_files(dir_path: &Path) -> Result<HashMap<PathBuf, u64>, std::io::Error> {
+    let mut files = HashMap::new();
+    let mut dirs = vec![dir_path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let mut read_dir = fs::read_dir(&dir).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let metadata = fs::metadata(entry.path()).await?;
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                files.insert(entry.path().strip_prefix(dir_path).unwrap().to_path_buf(), metadata.len());
+            }
+        }
+    }
+    Ok(files)
+}
+
+// Reads the data of the current entry
+async fn md5sum_entry(archive: &Archive) -> Result<String, ArchiveError> {
+    let mut hasher = Md5::new();
+    loop {
+        let (status, bytes) = archive.read_data_block().await;
+        match status {
+            bindings::ARCHIVE_OK | bindings::ARCHIVE_WARN => {
+                if let Some(bytes) = bytes {
+                    hasher.update(&bytes);
+                }
+            }
+            bindings::ARCHIVE_EOF => return Ok(format!("{:x}", hasher.finalize())),
+            _ => return Err(ArchiveError::from_err_code(status, archive.get_err_msg().await)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff;
+    use std::path::{Path, PathBuf};
+
+    // A tar archive of regular files is simple enough to write by hand, and libarchive reads it like any other
+    fn write_tar(path: &Path, files: &[(&str, &[u8])]) {
+        let mut tar = vec![];
+        for (name, data) in files {
+            let mut header = [0u8; 512];
+            header[..name.len()].copy_from_slice(name.as_bytes());
+            header[100..107].copy_from_slice(b"0000644");
+            header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+            header[136..147].copy_from_slice(b"00000000000");
+            header[148..156].copy_from_slice(b"        ");
+            header[156] = b'0';
+            header[257..263].copy_from_slice(b"ustar\0");
+            header[263..265].copy_from_slice(b"00");
+            let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+            header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+            tar.extend_from_slice(&header);
+            tar.extend_from_slice(data);
+            tar.resize(tar.len().div_ceil(512) * 512, 0);
+        }
+        tar.resize(tar.len() + 1024, 0);
+        std::fs::write(path, tar).unwrap();
+    }
+
+    #[tokio::test]
+    async fn diff_archive_with_directory() {
+        let tmp = std::env::temp_dir().join(format!("{}-test-{}", env!("CARGO_CRATE_NAME"), uuid::Uuid::new_v4()));
+        let dir = tmp.join("Mod");
+        std::fs::create_dir_all(dir.join("meshes")).unwrap();
+        std::fs::write(dir.join("Mod.esp"), b"version 1").unwrap();
+        std::fs::write(dir.join("meshes/rock.nif"), b"rock").unwrap();
+        std::fs::write(dir.join("Mod.ini"), b"edited").unwrap();
+        std::fs::write(dir.join("user.cfg"), b"created by the user").unwrap();
+
+        let archive = tmp.join("Mod-2.tar");
+        write_tar(
+            &archive,
+            &[
+                ("Mod.esp", b"version 2"),
+                ("./meshes/rock.nif", b"rock"),
+                ("Mod.ini", b"default"),
+                ("textures/rock.dds", b"texture"),
+            ],
+        );
+
+        let diff = diff(&archive, &dir).await.unwrap();
+        std::fs::remove_dir_all(&tmp).unwrap();
+        assert_eq!(vec![PathBuf::from("textures/rock.dds")], diff.added);
+        // Mod.esp has the same size but another hash
+        assert_eq!(vec![PathBuf::from("Mod.esp"), PathBuf::from("Mod.ini")], diff.modified);
+        assert_eq!(vec![PathBuf::from("user.cfg")], diff.removed);
+        assert_eq!(1, diff.unchanged);
+        assert_eq!("1 added, 2 modified, 1 removed, 1 unchanged.", diff.to_string());
+    }
+}
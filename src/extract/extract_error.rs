@@ -7,6 +7,7 @@ pub enum InstallError {
     AlreadyExists,
     ArchiveDeleted,
     ArchiveError { source: ArchiveError },
+    Cancelled,
     InProgress,
     IO { source: std::io::Error },
 }
@@ -17,6 +18,7 @@ impl Error for InstallError {
             InstallError::AlreadyExists => None,
             InstallError::ArchiveDeleted => None,
             InstallError::ArchiveError { ref source } => Some(source),
+            InstallError::Cancelled => None,
             InstallError::InProgress => None,
             InstallError::IO { ref source } => Some(source),
         }
@@ -29,6 +31,7 @@ impl fmt::Display for InstallError {
             InstallError::AlreadyExists => f.write_str("Target directory already exists."),
             InstallError::ArchiveDeleted => f.write_str("Archive no longer exists."),
             InstallError::ArchiveError { source } => source.fmt(f),
+            InstallError::Cancelled => f.write_str("Extraction was cancelled."),
             InstallError::InProgress => f.write_str("Extracting to target directory is already in progress."),
             InstallError::IO { source } => source.fmt(f),
         }
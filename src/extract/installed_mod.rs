@@ -1,5 +1,6 @@
 use crate::api::update_status::*;
 use crate::cache::{ArchiveFile, ArchiveMetadata, Cacheable};
+use crate::util::time;
 use crate::Cache;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::AtomicU64;
@@ -36,6 +37,9 @@ pub struct InstalledMod {
     pub repository: ModRepository,
     pub last_update_check: Arc<AtomicU64>,
     pub update_status: UpdateStatusWrapper,
+    // Missing from mods installed by older versions, in which case the directory's modification time is used instead
+    #[serde(default)]
+    pub installed_timestamp: u64,
 }
 
 impl ModDirectory {
@@ -77,6 +81,7 @@ impl ModDirectory {
                 repository: ModRepository::Nexus,
                 last_update_check: AtomicU64::new(0).into(),
                 update_status,
+                installed_timestamp: time::now(),
             }
             .into(),
         )
@@ -140,6 +140,10 @@ impl Entry {
     pub async fn path(&self) -> PathBuf {
         self.inner.lock().await.path()
     }
+
+    pub async fn size(&self) -> u64 {
+        self.inner.lock().await.size()
+    }
 }
 
 impl EntryWrapper {
@@ -164,6 +168,11 @@ impl EntryWrapper {
             osstr.into()
         }
     }
+
+    // Uncompressed size in bytes
+    fn size(&self) -> u64 {
+        unsafe { bindings::archive_entry_size(self.entry) as u64 }
+    }
 }
 
 #[derive(Debug)]
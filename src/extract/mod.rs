@@ -1,7 +1,9 @@
+mod archive_diff;
 mod extract_error;
 pub mod installed_mod;
 mod libarchive;
 
+pub use self::archive_diff::ArchiveDiff;
 pub use self::extract_error::InstallError;
 pub use self::installed_mod::*;
 
@@ -16,9 +18,12 @@ use std::sync::Arc;
 use tokio::fs::{self, File};
 use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
-use tokio::task;
+use tokio::task::{self, JoinHandle};
 use tokio_util::sync::CancellationToken;
 
+// Directories replaced by an upgrade are kept here. It has no metadata file, so it isn't listed as an installed mod.
+const BACKUP_DIR: &str = ".dmodman-backup";
+
 #[derive(Clone)]
 pub struct Installer {
     cache: Cache,
@@ -63,12 +68,50 @@ impl Installer {
         None
     }
 
+    /* Compares an archive with an installed directory before it's extracted there, so that files edited or added to the
+     * directory aren't overwritten unknowingly. */
+    pub async fn diff(&self, archive_name: &str, dir_name: &String) -> Result<ArchiveDiff, InstallError> {
+        let Some(ArchiveEntry::File(archive)) = self.cache.archives.get(archive_name).await else {
+            return Err(InstallError::ArchiveDeleted);
+        };
+        let archive_path = self.config.download_dir().join(&archive.file_name);
+        let dir_path = self.config.install_dir().join(dir_name);
+        let mut diff = archive_diff::diff(&archive_path, &dir_path).await?;
+        let metadata_path = PathBuf::from(DataPath::ModDirMetadata(&self.config, dir_name));
+        diff.removed.retain(|path| dir_path.join(path) != metadata_path);
+        Ok(diff)
+    }
+
+    // Logs a summary of the diff followed by the files that differ
+    pub async fn log_diff(&self, archive_name: &str, dir_name: &String) {
+        match self.diff(archive_name, dir_name).await {
+            Ok(diff) => {
+                self.logger.log(format!("Comparing {archive_name} with {dir_name}: {diff}"));
+                for line in diff.lines() {
+                    self.logger.log(line);
+                }
+            }
+            Err(e) => self.logger.log(format!("Unable to compare {archive_name} with {dir_name}: {e}")),
+        }
+    }
+
     pub async fn extract(
         &self,
         archive_name: String,
         dest_dir_name: String,
         overwrite: bool,
     ) -> Result<(), InstallError> {
+        self.start_extract(archive_name, dest_dir_name, overwrite).await.map(|_| ())
+    }
+
+    /* Extraction runs in the background. The returned handle finishes with its result, and with
+     * InstallError::Cancelled if the extraction was cancelled, in which case the target directory has been removed. */
+    async fn start_extract(
+        &self,
+        archive_name: String,
+        dest_dir_name: String,
+        overwrite: bool,
+    ) -> Result<JoinHandle<Result<(), InstallError>>, InstallError> {
         let src_path = self.config.download_dir().join(&archive_name);
         let mut dest_path = self.config.install_dir();
         dest_path.push(&dest_dir_name);
@@ -89,7 +132,7 @@ impl Installer {
              * unlikely to actually happen */
             None => {
                 self.logger.log(format!("{} no longer exists in the database..?", archive_name));
-                return Ok(());
+                return Err(InstallError::ArchiveDeleted);
             }
         };
 
@@ -99,75 +142,103 @@ impl Installer {
         }
 
         let me = self.clone();
-        let target_dir_name = dest_dir_name.clone();
         let cancel_token = CancellationToken::new();
         let cloned_token = cancel_token.clone();
         jobs.insert(archive_name.clone(), cancel_token);
-        task::spawn(async move {
+        Ok(task::spawn(async move {
             /* The select macro runs both futures at once, then allows us to run a function after the first one
              * finishes. It's well suited for cancelling a task and cleaning up afterwards.
              */
             tokio::select! {
                 _ = cloned_token.cancelled() => {
-                    if let Err(e) = fs::remove_dir_all(dest_path).await {
+                    if let Err(e) = fs::remove_dir_all(&dest_path).await {
                         me.logger.log(format!("Unable to remove target directory: {e}"));
                     }
+                    Err(InstallError::Cancelled)
                 },
-                _ = async {
-                    let mod_dir = me.pre_extract(archive_file.clone(), &dest_path, &target_dir_name).await;
-
-                    let res: Result<(), InstallError> = match Archive::open(src_path.to_string_lossy().to_string()).await {
-                        Ok(archive) => {
-                            //let mut reader = AsyncArchiveReader::new(archive);
-                            while let Some(entry_res) = archive.next().await {
-                                let entry = {
-                                    if let Err(e) = entry_res {
-                                        me.logger.log(format!("Unable to get next archive entry: {e}."));
-                                        let err: InstallError = e.into();
-                                        return Err(err);
-                                    }
-                                    entry_res.unwrap()
-                                };
-                                let target_path = dest_path.join(normalize_path(&entry.path().await));
-                                let name = target_path.file_name().unwrap().to_string_lossy();
-                                crate::logger::log_to_file(format!("Archive: {name}"));
-                                if entry.is_dir().await {
-                                    drop(entry);
-                                    if let Err(e) = fs::create_dir_all(&target_path).await {
-                                        me.logger.log(format!("Failed to extract directory {name}: {e}"));
-                                        let mut jobs = me.extract_jobs.write().await;
-                                        jobs.remove(&target_dir_name);
-                                        return Err(e.into());
-                                    }
-                                } else {
-                                    let parent = target_path.parent().unwrap_or(&dest_path);
-                                    if !parent.exists() {
-                                        std::fs::create_dir_all(parent).unwrap();
-                                    }
-                                    extract_entry(me.logger.clone(), target_path, archive.clone()).await.unwrap();
-                                    crate::logger::log_to_file("Done with first file...?");
-                                }
-                            }
-                            Ok(())
-                        }
-                        Err(e) => Err(e.into()),
-                    };
+                res = me.extract_archive(archive_file, &src_path, &dest_path, &dest_dir_name) => res
+            }
+        }))
+    }
 
-                    if res.is_ok() {
-                        me.post_extract(archive_file, target_dir_name, mod_dir).await;
-                    } else {
-                        *archive_file.install_state.write().await = ArchiveStatus::Error;
-                        me.cache.archives.has_changed.store(true, Ordering::Relaxed);
-                        // TODO maybe clean up after a failed extraction?
-                        me.logger.log(format!("Aborted extracting \"{}\". Output directory has not been removed.", archive_name));
-                        me.extract_jobs.write().await.remove(&target_dir_name);
-                    }
+    async fn extract_archive(
+        &self,
+        archive_file: Arc<ArchiveFile>,
+        src_path: &Path,
+        dest_path: &PathBuf,
+        dest_dir_name: &String,
+    ) -> Result<(), InstallError> {
+        let mod_dir = self.pre_extract(archive_file.clone(), dest_path, dest_dir_name).await;
+        let res = self.extract_entries(src_path, dest_path).await;
+        if res.is_ok() {
+            self.post_extract(archive_file, dest_dir_name.clone(), mod_dir).await;
+        } else {
+            *archive_file.install_state.write().await = ArchiveStatus::Error;
+            self.cache.archives.has_changed.store(true, Ordering::Relaxed);
+            // TODO maybe clean up after a failed extraction?
+            self.logger.log(format!(
+                "Aborted extracting \"{}\". Output directory has not been removed.",
+                archive_file.file_name
+            ));
+            self.extract_jobs.write().await.remove(&archive_file.file_name);
+        }
+        res
+    }
 
-                    Ok(())
-                } => {
+    async fn extract_entries(&self, src_path: &Path, dest_path: &Path) -> Result<(), InstallError> {
+        let archive = Archive::open(src_path.to_string_lossy().to_string()).await?;
+        while let Some(entry_res) = archive.next().await {
+            let entry =
+                entry_res.inspect_err(|e| self.logger.log(format!("Unable to get next archive entry: {e}.")))?;
+            let target_path = dest_path.join(normalize_path(&entry.path().await));
+            let name = target_path.file_name().unwrap().to_string_lossy();
+            crate::logger::log_to_file(format!("Archive: {name}"));
+            if entry.is_dir().await {
+                drop(entry);
+                if let Err(e) = fs::create_dir_all(&target_path).await {
+                    self.logger.log(format!("Failed to extract directory {name}: {e}"));
+                    return Err(e.into());
                 }
+            } else {
+                let parent = target_path.parent().unwrap_or(dest_path);
+                if !parent.exists() {
+                    fs::create_dir_all(parent).await?;
+                }
+                extract_entry(self.logger.clone(), target_path.clone(), archive.clone()).await?;
+                crate::logger::log_to_file("Done with first file...?");
             }
-        });
+        }
+        Ok(())
+    }
+
+    /* Reinstalls an installed mod from another archive into the same directory, which keeps its place in the load order.
+     * The old directory is moved to the backup directory, replacing any earlier backup of it. If the extraction fails
+     * or is cancelled, the old directory is moved back. */
+    pub async fn upgrade(&self, dir_name: String, archive_name: String) -> Result<(), InstallError> {
+        if self.extract_jobs.read().await.contains_key(&archive_name) {
+            return Err(InstallError::InProgress);
+        }
+        let dir_path = self.config.install_dir().join(&dir_name);
+        let backup_path = self.config.install_dir().join(BACKUP_DIR).join(&dir_name);
+        if backup_path.exists() {
+            fs::remove_dir_all(&backup_path).await?;
+        }
+        fs::create_dir_all(self.config.install_dir().join(BACKUP_DIR)).await?;
+        fs::rename(&dir_path, &backup_path).await?;
+
+        let res = match self.start_extract(archive_name, dir_name.clone(), false).await {
+            Ok(handle) => handle.await.unwrap_or_else(|e| Err(std::io::Error::other(e).into())),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            if dir_path.exists() {
+                fs::remove_dir_all(&dir_path).await?;
+            }
+            fs::rename(&backup_path, &dir_path).await?;
+            self.logger.log(format!("Restored the previous version of {dir_name}."));
+            return Err(e);
+        }
+        self.logger.log(format!("Moved the previous version of {dir_name} to {backup_path:?}"));
         Ok(())
     }
 
@@ -285,3 +356,24 @@ fn normalize_path(path: &Path) -> PathBuf {
     }
     ret
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BACKUP_DIR;
+    use crate::api::mock_server::MockEnv;
+
+    #[tokio::test]
+    async fn restore_after_failed_upgrade() {
+        let archive = "GH TR - PT Meshes-46599-1-01-1556986716.7z";
+        let env = MockEnv::with_archives(&[archive]).await;
+        std::fs::write(env.config.download_dir().join(archive), b"not an archive").unwrap();
+        let dir = env.config.install_dir().join("GH TR");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("GH TR.esp"), b"old version").unwrap();
+
+        let res = env.downloads.installer.upgrade("GH TR".to_string(), archive.to_string()).await;
+        assert!(res.is_err());
+        assert_eq!(b"old version".to_vec(), std::fs::read(dir.join("GH TR.esp")).unwrap());
+        assert!(!env.config.install_dir().join(BACKUP_DIR).join("GH TR").exists());
+    }
+}
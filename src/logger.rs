@@ -12,6 +12,7 @@ pub struct Logger {
     pub messages: Arc<RwLock<Vec<String>>>,
     pub has_changed: Arc<AtomicBool>, // used by UI to ask if error list needs to be redrawn
     is_interactive: bool,
+    use_stderr: bool,
 }
 
 impl Logger {
@@ -23,10 +24,21 @@ impl Logger {
         }
     }
 
+    // For commands whose output is read by other programs, so that messages aren't mixed with it
+    pub fn to_stderr() -> Self {
+        Self {
+            use_stderr: true,
+            ..Self::new(false)
+        }
+    }
+
     // TODO allow optionally logging to file (maybe with log levels?)
     pub fn log<S: Into<String> + Debug + Display>(&self, msg: S) {
         if !self.is_interactive {
-            println!("{}", msg);
+            match self.use_stderr {
+                true => eprintln!("{}", msg),
+                false => println!("{}", msg),
+            }
             return;
         }
 
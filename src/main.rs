@@ -1,5 +1,6 @@
 mod api;
 mod cache;
+mod check_updates;
 mod config;
 mod extract;
 mod logger;
@@ -7,7 +8,7 @@ mod nxm_socket;
 mod ui;
 mod util;
 
-use api::{Client, Downloads, Query};
+use api::{ApiError, Client, Downloads, Queriable, Query, UpdateChecker, UserDetails, OFFLINE_MSG};
 use cache::Cache;
 use config::{Config, ConfigBuilder, ConfigError};
 use logger::Logger;
@@ -16,62 +17,71 @@ use std::error::Error;
 use std::io::ErrorKind;
 use std::sync::Arc;
 
+const REFRESH_GAMES_AFTER_SECS: u64 = 7 * 24 * 60 * 60;
+
 /* dmodman acts as an url handler for nxm:// links in order for the "download with mod manager" button to work on
  * NexusMods.
  * If the program is invoked without argument, it starts the TUI unless another instance is already running.
  * If an nxm:// link is passed as an argument, we try to queue it in an already running instance. If none exists, we
  * start the TUI normally and queue the download.
+ * -d runs dmodman as a daemon without the TUI, and --offline starts it in offline mode.
+ * check-updates prints a report of the update status of downloaded files instead, as JSON with --json.
  */
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let mut nxm_str_opt: Option<&str> = None;
     let mut is_interactive = true;
+    let mut start_offline = false;
+    let mut check_updates = false;
+    let mut json = false;
 
     let args: Vec<String> = args().collect();
-    if args.len() > 2 {
-        println!("Too many arguments. Invoke dmodman without arguments or with an nxm:// URL.");
-        return Ok(());
-    }
-    if let Some(first_arg) = args.get(1) {
-        if first_arg.starts_with("nxm://") {
-            nxm_str_opt = Some(first_arg);
-        } else if first_arg == "-d" {
+    for arg in args.iter().skip(1) {
+        if arg.starts_with("nxm://") && nxm_str_opt.is_none() {
+            nxm_str_opt = Some(arg);
+        } else if arg == "-d" {
             is_interactive = false;
+        } else if arg == "--offline" {
+            start_offline = true;
+        } else if arg == "check-updates" {
+            check_updates = true;
+        } else if arg == "--json" {
+            json = true;
         } else {
-            // TODO use clap, this isn't true
-            println!("Arguments are expected only when acting as an nxm:// URL handler.");
+            // TODO use clap
+            println!("Unrecognized argument \"{arg}\". Invoke dmodman with -d, --offline or an nxm:// URL.");
+            println!("Update reports are printed with check-updates [--json] [--offline].");
             return Ok(());
         }
     }
+    if json && !check_updates {
+        println!("--json is only used with check-updates.");
+        return Ok(());
+    }
 
-    /* We can't println in the TUI. Instead we use Logger which can log to a file and show messages in the TUI.
-     * It calls println!() instead when running as a daemon. */
-    let logger = Logger::new(is_interactive);
-
-    let mut config = match ConfigBuilder::load(logger.clone()) {
-        Ok(config) => config.build(),
-        Err(e) => match e {
-            ConfigError::IO { source: _ } => ConfigBuilder::default().build(),
-            _ => Err(e),
-        },
-    }?;
-    if config.apikey.is_none() {
-        if let Some(apikey) = ui::sso::start_apikey_flow().await {
-            config.apikey = Some(apikey);
-            config.save_apikey()?;
-        } else {
-            logger.log("No API key configured. API connections are disabled.");
+    // The report doesn't need the socket, so it can be made while another instance is running
+    if check_updates {
+        let logger = Logger::to_stderr();
+        let mut config = load_config(&logger)?;
+        // There's no one to create an API key for, so the check is made from the cache instead
+        config.offline |= start_offline || config.apikey.is_none();
+        if config.offline {
+            logger.log(OFFLINE_MSG);
+        }
+        let exit_code = check_updates::check_updates(Arc::new(config), logger, json).await?;
+        if exit_code != 0 {
+            std::process::exit(exit_code);
         }
+        return Ok(());
     }
-    let config = Arc::new(config);
 
-    let cache = Cache::new(config.clone(), logger.clone()).await?;
-    let client = Client::new(&config).await;
-    let query = Query::new(cache.clone(), client.clone(), config.clone(), logger.clone());
-    let downloads = Downloads::new(cache.clone(), client.clone(), config.clone(), logger.clone(), query.clone()).await;
+    /* We can't println in the TUI. Instead we use Logger which can log to a file and show messages in the TUI.
+     * It calls println!() instead when running as a daemon. */
+    let logger = Logger::new(is_interactive);
 
-    // Try bind to /run/user/$uid. If it already exists then send any nxm:// link through the socket and quit.
+    /* Try bind to /run/user/$uid. If it already exists then send any nxm:// link through the socket and quit.
+     * This is done before validating the API key, so passing on a download doesn't use up an API request. */
     let nxm_socket = match nxm_socket::try_bind().await {
         Ok(nxm_socket) => nxm_socket,
         Err(e) if e.kind() == ErrorKind::AddrInUse => {
@@ -88,6 +98,88 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     };
 
+    let mut config = load_config(&logger)?;
+    config.offline |= start_offline;
+    /* A key that the Nexus rejects is replaced the same way as a missing one. Otherwise the client that validated it is
+     * kept, so that the request is counted towards the quota shown in the UI. */
+    let mut user_details = None;
+    let mut validated_client = None;
+    if config.apikey.is_some() && !config.offline {
+        let client = Client::new(&Arc::new(config.clone())).await?;
+        match UserDetails::request(&client, &[]).await {
+            Ok(details) => {
+                user_details = Some(details);
+                validated_client = Some(client);
+            }
+            Err(ApiError::Unauthorized) => {
+                println!("The Nexus rejected the API key. It might have been revoked.");
+                config.apikey = None;
+            }
+            Err(e) => {
+                logger.log(format!("Unable to validate API key: {e}"));
+                validated_client = Some(client);
+            }
+        }
+    }
+    if config.apikey.is_none() && !config.offline {
+        if let Some(apikey) = ui::sso::start_apikey_flow(&config).await {
+            config.apikey = Some(apikey);
+            config.save_apikey()?;
+        } else {
+            logger.log("No API key configured. API connections are disabled.");
+        }
+    }
+    // Without an API key every request would fail, so offline is reported once instead
+    if config.apikey.is_none() {
+        config.offline = true;
+    }
+    if config.offline && is_interactive {
+        logger.log(OFFLINE_MSG);
+    } else if config.offline {
+        logger.log("Offline mode: answering from cache. nxm:// links are dropped, since the daemon can't go online.");
+    }
+    let config = Arc::new(config);
+
+    let cache = Cache::new(config.clone(), logger.clone()).await?;
+    let client = match validated_client {
+        Some(client) => client,
+        None => Client::new(&config).await?,
+    };
+    let query = Query::new(cache.clone(), client.clone(), config.clone(), logger.clone());
+    let mut downloads =
+        Downloads::new(cache.clone(), client.clone(), config.clone(), logger.clone(), query.clone()).await;
+    if !is_interactive {
+        downloads.drop_links_while_offline();
+    }
+    match user_details {
+        Some(details) => cache.account.set(details).await,
+        // The key is new or the Nexus couldn't be reached, so the account is checked in the background
+        None if !config.offline => {
+            let query = query.clone();
+            tokio::task::spawn(async move {
+                let _ = query.validate().await;
+            });
+        }
+        None => {}
+    }
+
+    // The list of games rarely changes, so it's refreshed weekly
+    if !config.offline {
+        let query = query.clone();
+        let config = config.clone();
+        let msgs = logger.clone();
+        tokio::task::spawn(async move {
+            if let Err(e) = query.refresh_games(REFRESH_GAMES_AFTER_SECS).await {
+                msgs.log(format!("Unable to fetch list of games: {e}"));
+            }
+            if let Some(game) = &config.game {
+                if !query.is_known_game(game).await {
+                    msgs.log(format!("Warn: the profile's game \"{game}\" doesn't exist on the Nexus."));
+                }
+            }
+        });
+    }
+
     downloads.resume_on_startup().await;
 
     if let Some(nxm_str) = nxm_str_opt {
@@ -104,11 +196,38 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 nxm_socket::listen_for_downloads(nxm_socket, downloads, msgs).await;
             });
         }
+        // The tracked mods are cached, so the UI can show those while the list is refreshed
+        if !config.offline {
+            let query = query.clone();
+            let msgs = logger.clone();
+            tokio::task::spawn(async move {
+                if let Err(e) = query.tracked_mods().await {
+                    msgs.log(format!("Unable to fetch tracked mods: {e}"));
+                }
+            });
+        }
 
         ui::MainUI::new(cache, client, config, downloads, logger, query).await.run().await;
     } else {
+        if let Some(interval) = config.update_interval {
+            let updater = UpdateChecker::new(cache, client, config.clone(), logger.clone(), query);
+            tokio::task::spawn(async move {
+                updater.schedule(interval).await;
+            });
+        }
         nxm_socket::listen_for_downloads(nxm_socket, downloads, logger).await;
     }
 
     Ok(())
 }
+
+// A missing config file is the same as an empty one
+fn load_config(logger: &Logger) -> Result<Config, ConfigError> {
+    match ConfigBuilder::load(logger.clone()) {
+        Ok(config) => config.build(),
+        Err(e) => match e {
+            ConfigError::IO { source: _ } => ConfigBuilder::default().build(),
+            _ => Err(e),
+        },
+    }
+}
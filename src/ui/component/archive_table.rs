@@ -14,6 +14,7 @@ pub struct ArchiveTable<'a> {
     widths: [Constraint; 4],
     pub cache: Cache,
     pub currently_shown: IndexMap<String, ArchiveEntry>,
+    at_risk_only: bool,
     pub neighbors: NeighboringWidgets,
     pub block: Block<'a>,
     pub highlight_style: Style,
@@ -45,6 +46,7 @@ impl ArchiveTable<'_> {
             headers,
             widths,
             currently_shown: IndexMap::new(),
+            at_risk_only: false,
             cache,
             neighbors,
             block,
@@ -60,31 +62,37 @@ impl ArchiveTable<'_> {
         if self.cache.archives.has_changed.swap(false, Ordering::Relaxed) {
             let mut rows: Vec<Row> = vec![];
             let archives_lock = self.cache.archives.files.read().await;
-            self.currently_shown = archives_lock.clone();
-            for (i, (archive_name, entry)) in archives_lock.iter().enumerate() {
-                let install_status = match &entry {
-                    ArchiveEntry::File(archive) => archive.install_state.read().await.to_string(),
-                    ArchiveEntry::MetadataOnly(_) => "Deleted".to_string(),
-                };
-                let update_status = match entry.metadata() {
+            self.currently_shown = IndexMap::new();
+            for (archive_name, entry) in archives_lock.iter() {
+                let (flags, at_risk) = match entry.metadata() {
                     Some(metadata) => {
                         let mfd = self.cache.metadata_index.get_by_file_id(&metadata.file_id).await.unwrap();
-                        format_update_status_flags(&mfd.update_status)
+                        let mod_info = mfd.mod_info().await;
+                        let at_risk = mod_info.as_ref().is_some_and(|mi| mi.is_unavailable());
+                        (format_flags(&mfd.update_status, mod_info.as_deref()), at_risk)
                     }
-                    None => "".into(),
+                    None => ("".into(), false),
+                };
+                if self.at_risk_only && !at_risk {
+                    continue;
+                }
+                let install_status = match &entry {
+                    ArchiveEntry::File(archive) => archive.install_state.read().await.to_string(),
+                    ArchiveEntry::MetadataOnly(_) => "Deleted".to_string(),
                 };
                 rows.push(
                     Row::new(vec![
                         Cell::new(archive_name.clone()),
                         Cell::new(install_status),
-                        Cell::new(update_status),
+                        Cell::new(flags),
                         Cell::new(match entry {
                             ArchiveEntry::File(archive) => util::format::human_readable(archive.size).0,
                             _ => "".to_string(),
                         }),
                     ])
-                    .style(LIST_STYLES[i % 2]),
-                )
+                    .style(LIST_STYLES[rows.len() % 2]),
+                );
+                self.currently_shown.insert(archive_name.clone(), entry.clone());
             }
             self.len = rows.len();
             self.widget = Table::new(rows, self.widths)
@@ -96,6 +104,18 @@ impl ArchiveTable<'_> {
         false
     }
 
+    /* Lists only the archives of mods that are no longer available on the Nexus, so that they can be backed up. Toggled
+     * while the table is focused, so the block stays highlighted. */
+    pub fn toggle_at_risk_filter(&mut self) {
+        self.at_risk_only = !self.at_risk_only;
+        let title = match self.at_risk_only {
+            true => " Archives of mods at risk ",
+            false => " Archives ",
+        };
+        self.block = DEFAULT_BLOCK.title(title).border_style(BLOCK_HIGHLIGHT_STYLE);
+        self.cache.archives.has_changed.store(true, Ordering::Relaxed);
+    }
+
     pub fn get_by_index(&self, index: usize) -> (&String, &ArchiveEntry) {
         self.currently_shown.get_index(index).unwrap()
     }
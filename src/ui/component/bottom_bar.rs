@@ -1,5 +1,7 @@
+use super::common::UNAVAILABLE_STYLE;
 use super::{ArchiveTable, DownloadsTable, InstalledModsTable};
-use crate::api::UpdateStatus;
+use crate::api::{EndorseStatus, ModInfo, Replacement, UpdateStatus};
+use crate::cache::ModFileMetadata;
 use crate::extract::ModDirectory;
 use crate::ui::navigation::Focused;
 use crate::Cache;
@@ -11,6 +13,7 @@ use std::sync::Arc;
 
 const STYLE_OUTOFDATE: Style = Style::new().fg(Color::Red);
 const STYLE_HASNEWFILE: Style = Style::new().fg(Color::Yellow);
+const STYLE_ENDORSED: Style = Style::new().fg(Color::Green);
 
 pub struct BottomBar<'a> {
     cache: Cache,
@@ -49,14 +52,30 @@ impl<'a> BottomBar<'a> {
                     Focused::InstalledMods => {
                         let (_, mod_dir) = installed.get_by_index(focused_index);
                         if let ModDirectory::Nexus(im) = mod_dir {
+                            let mfd = self.cache.metadata_index.get_by_file_id(&im.file_id).await;
                             let mut modname = StatusField::from_mod_name(im.mod_name.clone());
                             if modname.is_none() {
-                                if let Some(mfd) = self.cache.metadata_index.get_by_file_id(&im.file_id).await {
+                                if let Some(mfd) = &mfd {
                                     modname = StatusField::from_mod_name(mfd.mod_name().await);
                                 }
                             }
-                            let flags = StatusField::from_update_status(im.update_status.to_enum());
-                            self.widget = Paragraph::new(Line::from(format_fields(vec![modname, flags])));
+                            let flags = update_flags(im.update_status.to_enum(), mfd.as_deref()).await;
+                            let mod_info = self.cache.mod_info.get(&im.game, im.mod_id).await;
+                            let endorsement = StatusField::from_endorse_status(mod_info.as_deref());
+                            let availability = StatusField::from_availability(mod_info.as_deref());
+                            let mut category = None;
+                            if let Some(mi) = mod_info {
+                                if let Some(name) = self.cache.games.category_name(&im.game, mi.category_id).await {
+                                    category = Some(StatusField::new("Category", name));
+                                }
+                            }
+                            self.widget = Paragraph::new(Line::from(format_fields(vec![
+                                modname,
+                                category,
+                                flags,
+                                availability,
+                                endorsement,
+                            ])));
                         } else {
                             self.widget = Paragraph::default();
                         }
@@ -66,8 +85,16 @@ impl<'a> BottomBar<'a> {
                         if let Some(metadata) = archive.metadata() {
                             if let Some(mfd) = self.cache.metadata_index.get_by_file_id(&metadata.file_id).await {
                                 let modname = mfd.mod_name().await.map(|n| StatusField::new("Mod", n.clone()));
-                                let flags = StatusField::from_update_status(mfd.update_status.to_enum());
-                                self.widget = Paragraph::new(Line::from(format_fields(vec![modname, flags])));
+                                let flags = update_flags(mfd.update_status.to_enum(), Some(&mfd)).await;
+                                let mod_info = mfd.mod_info().await;
+                                let availability = StatusField::from_availability(mod_info.as_deref());
+                                let endorsement = StatusField::from_endorse_status(mod_info.as_deref());
+                                self.widget = Paragraph::new(Line::from(format_fields(vec![
+                                    modname,
+                                    flags,
+                                    availability,
+                                    endorsement,
+                                ])));
                             }
                         } else {
                             self.widget = Paragraph::default();
@@ -77,7 +104,7 @@ impl<'a> BottomBar<'a> {
                         let file_info = downloads.get_by_index(focused_index);
                         if let Some(mfd) = self.cache.metadata_index.get_by_file_id(&file_info.file_id).await {
                             let modname = mfd.mod_name().await.map(|n| StatusField::new("Mod", n.clone()));
-                            let flags = StatusField::from_update_status(mfd.update_status.to_enum());
+                            let flags = update_flags(mfd.update_status.to_enum(), Some(&mfd)).await;
                             self.widget = Paragraph::new(Line::from(format_fields(vec![modname, flags])));
                         } else {
                             self.widget = Paragraph::default();
@@ -120,6 +147,21 @@ impl<'a> StatusField<'a> {
         name.map(|name| StatusField::new("Mod", name.clone()).style(Style::default().fg(Color::White)))
     }
 
+    pub fn from_endorse_status(mod_info: Option<&ModInfo>) -> Option<Self> {
+        match mod_info?.endorsement.as_ref()?.endorse_status {
+            EndorseStatus::Endorsed => {
+                Some(StatusField::new("Endorsement", "Endorsed".to_string()).style(STYLE_ENDORSED))
+            }
+            EndorseStatus::Abstained => Some(StatusField::new("Endorsement", "Abstained".to_string())),
+            EndorseStatus::Undecided => Some(StatusField::new("Endorsement", "Undecided".to_string())),
+        }
+    }
+
+    pub fn from_availability(mod_info: Option<&ModInfo>) -> Option<Self> {
+        let mod_info = mod_info.filter(|mi| mi.is_unavailable())?;
+        Some(StatusField::new("Nexus", format!("Unavailable ({})", mod_info.status)).style(UNAVAILABLE_STYLE))
+    }
+
     pub fn from_update_status(update_status: UpdateStatus) -> Option<Self> {
         match update_status {
             UpdateStatus::OutOfDate(_) => {
@@ -128,9 +170,29 @@ impl<'a> StatusField<'a> {
             UpdateStatus::HasNewFile(_) => {
                 Some(StatusField::new("Flags", "Mod has new file".to_string()).style(STYLE_HASNEWFILE))
             }
+            UpdateStatus::IgnoredPermanently(_) => Some(StatusField::new("Flags", "Updates ignored".to_string())),
             _ => None,
         }
     }
+
+    // Shown as "v1.2 -> v1.5 (MyMod-123-1-5.7z)"
+    pub fn from_replacement(version: Option<String>, replacement: Replacement) -> Self {
+        let version = version.unwrap_or("?".to_string());
+        let new_version = replacement.version.unwrap_or("?".to_string());
+        StatusField::new("Update", format!("v{version} -> v{new_version} ({})", replacement.file_name))
+            .style(STYLE_OUTOFDATE)
+    }
+}
+
+// Out of date files show the version they're replaced by, if the update chain leads to one
+async fn update_flags<'a>(update_status: UpdateStatus, mfd: Option<&ModFileMetadata>) -> Option<StatusField<'a>> {
+    if let (UpdateStatus::OutOfDate(_), Some(mfd)) = (&update_status, mfd) {
+        if let Some(replacement) = mfd.replacement().await {
+            let version = mfd.file_details().await.and_then(|fd| fd.version.clone());
+            return Some(StatusField::from_replacement(version, replacement));
+        }
+    }
+    StatusField::from_update_status(update_status)
 }
 
 fn format_fields<'a>(fields: Vec<Option<StatusField<'a>>>) -> Vec<Span<'a>> {
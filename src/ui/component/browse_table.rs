@@ -0,0 +1,184 @@
+use super::common::*;
+use super::traits::Select;
+use crate::api::{ModInfo, ModList, Query};
+use crate::ui::navigation::*;
+use crate::Logger;
+use ratatui::layout::Constraint;
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+const LISTS: [ModList; 3] = [ModList::LatestAdded, ModList::LatestUpdated, ModList::Trending];
+
+/* Lists the latest added, latest updated and trending mods of the profile's game. Each list is fetched when it's first
+ * shown, and again only when reloaded. */
+pub struct BrowseTable<'a> {
+    headers: Row<'a>,
+    widths: [Constraint; 4],
+    game: Option<String>,
+    logger: Logger,
+    query: Query,
+    list_index: usize,
+    lists: Arc<RwLock<HashMap<ModList, Vec<Arc<ModInfo>>>>>,
+    requested: HashSet<ModList>,
+    pub has_changed: Arc<AtomicBool>,
+    pub currently_shown: Vec<Arc<ModInfo>>,
+    pub neighbors: NeighboringWidgets,
+    pub block: Block<'a>,
+    pub highlight_style: Style,
+    pub state: TableState,
+    pub widget: Table<'a>,
+    pub len: usize,
+}
+
+impl<'a> BrowseTable<'a> {
+    pub fn new(game: Option<String>, logger: Logger, query: Query) -> Self {
+        let block = DEFAULT_BLOCK.border_style(BLOCK_STYLE);
+        let widths = [
+            Constraint::Ratio(6, 12),
+            Constraint::Ratio(3, 12),
+            Constraint::Ratio(1, 12),
+            Constraint::Ratio(2, 12),
+        ];
+
+        let headers = Row::new(vec![
+            Cell::from(header_text("Name")),
+            Cell::from(header_text("Author")),
+            Cell::from(header_text("Version")),
+            Cell::from(header_text("Date")),
+        ]);
+
+        Self {
+            headers,
+            widths,
+            game,
+            logger,
+            query,
+            list_index: 0,
+            lists: Default::default(),
+            requested: HashSet::new(),
+            has_changed: Arc::new(AtomicBool::new(true)),
+            currently_shown: vec![],
+            neighbors: NeighboringWidgets::new(),
+            block: block.clone(),
+            highlight_style: Style::default(),
+            state: TableState::default(),
+            widget: Table::default().widths(widths).block(block),
+            len: 0,
+        }
+    }
+
+    pub async fn refresh(&mut self) -> bool {
+        let list = LISTS[self.list_index];
+        if let Some(game) = &self.game {
+            if self.requested.insert(list) {
+                self.fetch(game.clone(), list);
+            }
+        }
+        if !self.has_changed.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+
+        let mut rows: Vec<Row> = vec![];
+        self.currently_shown = self.lists.read().await.get(&list).cloned().unwrap_or_default();
+        for mi in &self.currently_shown {
+            // Newly added mods are more interesting by their creation date
+            let date = match list {
+                ModList::LatestAdded => &mi.created_time,
+                _ => &mi.updated_time,
+            };
+            let row = Row::new(vec![
+                Cell::from(mi.name.clone().unwrap_or_default()),
+                Cell::from(mi.author.clone()),
+                Cell::from(mi.version.clone().unwrap_or_default()),
+                Cell::from(date.get(..10).unwrap_or(date).to_string()),
+            ])
+            .style(LIST_STYLES[rows.len() % 2]);
+            rows.push(row);
+        }
+        self.len = rows.len();
+        // A reloaded list can be shorter than the selection
+        let selected = self.selected();
+        self.select(selected);
+
+        // The table is the only widget in its tab, so it's focused whenever it's refreshed
+        let title = match &self.game {
+            Some(game) => {
+                let titles: Vec<String> = LISTS
+                    .iter()
+                    .map(|l| match *l == list {
+                        true => format!("[{l}]"),
+                        false => l.to_string(),
+                    })
+                    .collect();
+                format!(" {} mods for {game} ", titles.join(" | "))
+            }
+            None => " Set the game of the profile to browse its mods ".to_string(),
+        };
+        self.block = DEFAULT_BLOCK.title(title).border_style(BLOCK_HIGHLIGHT_STYLE);
+
+        self.widget = Table::new(rows, self.widths)
+            .header(self.headers.to_owned())
+            .block(self.block.to_owned())
+            .row_highlight_style(self.highlight_style.to_owned());
+        true
+    }
+
+    fn fetch(&self, game: String, list: ModList) {
+        let query = self.query.clone();
+        let logger = self.logger.clone();
+        let lists = self.lists.clone();
+        let has_changed = self.has_changed.clone();
+        tokio::task::spawn(async move {
+            match query.mod_list(&game, list).await {
+                Ok(mods) => {
+                    lists.write().await.insert(list, mods);
+                    has_changed.store(true, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    logger.log(format!("Unable to fetch the {} mods of {game}: {e}", list.to_string().to_lowercase()))
+                }
+            }
+        });
+    }
+
+    pub fn get_by_index(&self, index: usize) -> &ModInfo {
+        self.currently_shown.get(index).unwrap()
+    }
+
+    pub fn next_list(&mut self) {
+        self.list_index = (self.list_index + 1) % LISTS.len();
+        self.state.select(None);
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn previous_list(&mut self) {
+        self.list_index = (self.list_index + LISTS.len() - 1) % LISTS.len();
+        self.state.select(None);
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    // The shown list is fetched again on the next refresh
+    pub fn reload(&mut self) {
+        self.requested.remove(&LISTS[self.list_index]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BrowseTable;
+    use crate::api::mock_server::MockEnv;
+    use crate::ui::component::traits::Select;
+
+    #[tokio::test]
+    async fn clamp_selection_to_rows() {
+        let env = MockEnv::new().await;
+        let mut table = BrowseTable::new(None, env.logger.clone(), env.query.clone());
+        table.state.select(Some(3));
+        table.refresh().await;
+        assert_eq!(None, table.selected());
+    }
+}
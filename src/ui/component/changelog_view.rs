@@ -0,0 +1,179 @@
+use super::common::*;
+use super::{ArchiveTable, InstalledModsTable};
+use crate::api::{Query, UpdateStatus};
+use crate::extract::ModDirectory;
+use crate::ui::navigation::*;
+use crate::util::format;
+use crate::{Cache, Logger};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// The selected file that has an update, and the upload time of the newest file in its mod
+#[derive(Clone, PartialEq)]
+struct Outdated {
+    game: String,
+    mod_id: u32,
+    version: Option<String>,
+    latest_timestamp: u64,
+}
+
+/* Shows what changed since the selected file's version, if the file is out of date or its mod has a new file.
+ * Changelogs are fetched when the cached ones are older than the newest file of the mod. */
+pub struct ChangelogView<'a> {
+    cache: Cache,
+    logger: Logger,
+    query: Query,
+    selected: Option<Outdated>,
+    prev_focused: Focused,
+    prev_selected_index: Option<usize>,
+    // Mods whose changelogs have been requested, so that failed requests aren't repeated
+    requested: HashSet<(String, u32)>,
+    is_fetching: Arc<AtomicBool>,
+    pub has_changed: Arc<AtomicBool>,
+    pub is_shown: bool,
+    pub neighbors: NeighboringWidgets,
+    pub block: Block<'a>,
+    pub state: ListState,
+    pub highlight_style: Style,
+    pub widget: List<'a>,
+    pub len: usize,
+}
+
+impl<'a> ChangelogView<'a> {
+    pub fn new(cache: Cache, logger: Logger, query: Query, focused: Focused) -> Self {
+        let block = DEFAULT_BLOCK.title(" Changelog ").border_style(BLOCK_STYLE);
+
+        let mut neighbors = NeighboringWidgets::new();
+        neighbors
+            .map
+            .insert(Tab::Archives, Neighbors::default().left(Focused::ArchiveTable).up(Focused::DownloadTable));
+        neighbors.map.insert(Tab::Installed, Neighbors::default().left(Focused::InstalledMods));
+
+        Self {
+            cache,
+            logger,
+            query,
+            selected: None,
+            prev_focused: focused,
+            prev_selected_index: None,
+            requested: HashSet::new(),
+            is_fetching: Arc::new(AtomicBool::new(false)),
+            has_changed: Arc::new(AtomicBool::new(false)),
+            is_shown: false,
+            neighbors,
+            block: block.clone(),
+            state: ListState::default(),
+            highlight_style: Style::default(),
+            widget: List::default().block(block),
+            len: 0,
+        }
+    }
+
+    pub async fn refresh(
+        &mut self,
+        archives: &ArchiveTable<'_>,
+        installed: &InstalledModsTable<'_>,
+        focused: &Focused,
+        focused_index: Option<usize>,
+    ) -> bool {
+        // The selection only matters in the tables that have files
+        if let Focused::ArchiveTable | Focused::InstalledMods = focused {
+            if *focused != self.prev_focused || focused_index != self.prev_selected_index {
+                self.prev_focused = focused.clone();
+                self.prev_selected_index = focused_index;
+                let selected = match focused_index {
+                    Some(i) => self.outdated(archives, installed, focused, i).await,
+                    None => None,
+                };
+                if selected != self.selected {
+                    self.selected = selected;
+                    self.state.select(None);
+                    self.has_changed.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+        if !self.has_changed.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+
+        let was_shown = self.is_shown;
+        self.is_shown = self.selected.is_some();
+        let Some(outdated) = self.selected.clone() else {
+            return was_shown;
+        };
+
+        let changelogs = self.cache.changelogs.get(&outdated.game, outdated.mod_id).await;
+        let is_stale = changelogs.as_ref().is_none_or(|cl| cl.fetched < outdated.latest_timestamp);
+        if is_stale && self.requested.insert((outdated.game.clone(), outdated.mod_id)) {
+            let query = self.query.clone();
+            let logger = self.logger.clone();
+            let has_changed = self.has_changed.clone();
+            let is_fetching = self.is_fetching.clone();
+            is_fetching.store(true, Ordering::Relaxed);
+            tokio::task::spawn(async move {
+                if let Err(e) = query.changelogs(&outdated.game, outdated.mod_id).await {
+                    logger.log(format!("Unable to fetch changelogs of mod {}: {e}", outdated.mod_id));
+                }
+                is_fetching.store(false, Ordering::Relaxed);
+                has_changed.store(true, Ordering::Relaxed);
+            });
+        }
+
+        let mut items = vec![];
+        if let Some(changelogs) = &changelogs {
+            for (version, entries) in changelogs.newer_than(outdated.version.as_deref()) {
+                items.push(ListItem::new(Line::styled(version.to_string(), Style::new().add_modifier(Modifier::BOLD))));
+                for entry in entries {
+                    let text = format::html_to_text(entry);
+                    items.push(ListItem::new(Text::from_iter(text.lines().map(|line| format!("  {line}")))));
+                }
+            }
+        }
+        if items.is_empty() {
+            let msg = match self.is_fetching.load(Ordering::Relaxed) {
+                true => "Fetching changelog...",
+                false => "No changelog entries for newer versions.",
+            };
+            items.push(ListItem::new(msg));
+        }
+
+        self.len = items.len();
+        self.widget = List::new(items).block(self.block.clone()).highlight_style(self.highlight_style);
+        true
+    }
+
+    async fn outdated(
+        &self,
+        archives: &ArchiveTable<'_>,
+        installed: &InstalledModsTable<'_>,
+        focused: &Focused,
+        index: usize,
+    ) -> Option<Outdated> {
+        let (game, mod_id, version, update_status) = match focused {
+            Focused::InstalledMods => match installed.get_by_index(index).1 {
+                ModDirectory::Nexus(im) => (im.game.clone(), im.mod_id, im.version.clone(), im.update_status.to_enum()),
+                _ => return None,
+            },
+            Focused::ArchiveTable => {
+                let metadata = archives.get_by_index(index).1.metadata()?;
+                let mfd = self.cache.metadata_index.get_by_file_id(&metadata.file_id).await?;
+                let version = mfd.file_details().await.and_then(|fd| fd.version.clone());
+                (mfd.game.clone(), mfd.mod_id, version, mfd.update_status.to_enum())
+            }
+            _ => return None,
+        };
+        match update_status {
+            UpdateStatus::OutOfDate(latest_timestamp) | UpdateStatus::HasNewFile(latest_timestamp) => Some(Outdated {
+                game,
+                mod_id,
+                version,
+                latest_timestamp,
+            }),
+            _ => None,
+        }
+    }
+}
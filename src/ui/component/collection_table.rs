@@ -0,0 +1,150 @@
+use super::common::*;
+use crate::api::{CollectionModFile, Downloads};
+use crate::cache::ArchiveEntry;
+use crate::ui::navigation::*;
+use crate::Cache;
+use ratatui::layout::Constraint;
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use std::collections::HashSet;
+use std::sync::atomic::Ordering;
+
+#[derive(Clone, Copy, PartialEq)]
+enum FileStatus {
+    Downloaded,
+    Downloading,
+    Missing,
+    Optional,
+}
+
+impl FileStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FileStatus::Downloaded => "Downloaded",
+            FileStatus::Downloading => "Downloading",
+            FileStatus::Missing => "Missing",
+            FileStatus::Optional => "Optional",
+        }
+    }
+}
+
+/* Lists the files of the most recently opened collection, along with whether they've been downloaded. Users without
+ * premium can use it as a checklist of pages to visit on the Nexus. */
+pub struct CollectionTable<'a> {
+    headers: Row<'a>,
+    widths: [Constraint; 4],
+    cache: Cache,
+    downloads: Downloads,
+    statuses: Vec<FileStatus>,
+    pub currently_shown: Vec<CollectionModFile>,
+    pub neighbors: NeighboringWidgets,
+    pub block: Block<'a>,
+    pub highlight_style: Style,
+    pub state: TableState,
+    pub widget: Table<'a>,
+    pub len: usize,
+}
+
+impl<'a> CollectionTable<'a> {
+    pub fn new(cache: Cache, downloads: Downloads) -> Self {
+        let block = DEFAULT_BLOCK.title(" Open a collection on the Nexus to list its files ").border_style(BLOCK_STYLE);
+        let widths = [
+            Constraint::Ratio(5, 12),
+            Constraint::Ratio(4, 12),
+            Constraint::Ratio(1, 12),
+            Constraint::Ratio(2, 12),
+        ];
+
+        let headers = Row::new(vec![
+            Cell::from(header_text("Mod")),
+            Cell::from(header_text("File")),
+            Cell::from(header_text("Version")),
+            Cell::from(header_text("Status")),
+        ]);
+
+        Self {
+            headers,
+            widths,
+            cache,
+            downloads,
+            statuses: vec![],
+            currently_shown: vec![],
+            neighbors: NeighboringWidgets::new(),
+            block: block.clone(),
+            highlight_style: Style::default(),
+            state: TableState::default(),
+            widget: Table::default().widths(widths).block(block.clone()),
+            len: 0,
+        }
+    }
+
+    /* Files can be downloaded from elsewhere, such as the Nexus, so the statuses are checked on every refresh. The
+     * table is only rebuilt if the collection or some status has changed. */
+    pub async fn refresh(&mut self) -> bool {
+        let collection_changed = self.cache.collection.has_changed.swap(false, Ordering::Relaxed);
+        let Some(revision) = self.cache.collection.get().await else {
+            return false;
+        };
+
+        let archives: HashSet<u64> = self
+            .cache
+            .archives
+            .files
+            .read()
+            .await
+            .values()
+            .filter(|entry| matches!(entry, ArchiveEntry::File(_)))
+            .filter_map(|entry| entry.metadata().map(|md| md.file_id))
+            .collect();
+        let tasks = self.downloads.tasks.read().await;
+        let statuses: Vec<FileStatus> = revision
+            .mod_files
+            .iter()
+            .map(|mf| {
+                if archives.contains(&mf.file_id) {
+                    FileStatus::Downloaded
+                } else if tasks.contains_key(&mf.file_id) {
+                    FileStatus::Downloading
+                } else if mf.optional {
+                    FileStatus::Optional
+                } else {
+                    FileStatus::Missing
+                }
+            })
+            .collect();
+        drop(tasks);
+        if !collection_changed && statuses == self.statuses {
+            return false;
+        }
+
+        let mut rows: Vec<Row> = vec![];
+        for (mf, status) in revision.mod_files.iter().zip(&statuses) {
+            let row = Row::new(vec![
+                Cell::from(mf.file.mod_info.name.clone()),
+                Cell::from(mf.file.name.clone()),
+                Cell::from(mf.file.version.clone().unwrap_or_default()),
+                Cell::from(status.as_str()),
+            ])
+            .style(LIST_STYLES[rows.len() % 2]);
+            rows.push(row);
+        }
+        // The table is the only widget in its tab, so it's focused whenever it's refreshed
+        let missing = statuses.iter().filter(|s| **s == FileStatus::Missing).count();
+        let title =
+            format!(" {} (revision {}) | {missing} missing ", revision.collection.name, revision.revision_number);
+        self.block = DEFAULT_BLOCK.title(title).border_style(BLOCK_HIGHLIGHT_STYLE);
+        self.currently_shown = revision.mod_files.clone();
+        self.statuses = statuses;
+        self.len = rows.len();
+
+        self.widget = Table::new(rows, self.widths)
+            .header(self.headers.to_owned())
+            .block(self.block.to_owned())
+            .row_highlight_style(self.highlight_style.to_owned());
+        true
+    }
+
+    pub fn get_by_index(&self, index: usize) -> &CollectionModFile {
+        self.currently_shown.get(index).unwrap()
+    }
+}
@@ -1,6 +1,6 @@
-use crate::api::{UpdateStatus, UpdateStatusWrapper};
+use crate::api::{ModInfo, UpdateStatus, UpdateStatusWrapper};
 use ratatui::style::{Color, Modifier, Style, Stylize};
-use ratatui::text::{Span, Text};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, Padding};
 
 pub const DEFAULT_BLOCK: Block = Block::new().borders(Borders::ALL).padding(Padding::horizontal(1));
@@ -15,17 +15,29 @@ pub const LISTITEM_STYLE: Style = Style::new();
 pub const LISTITEM_ALTERNATE_STYLE: Style = Style::new().fg(Color::White);
 pub const LIST_STYLES: &[Style] = &[LISTITEM_ALTERNATE_STYLE, LISTITEM_STYLE];
 
+pub const UNAVAILABLE_STYLE: Style = Style::new().fg(Color::Magenta);
+
 pub fn header_text(name: &str) -> Text<'_> {
     Text::from(Span::from(name).style(HEADER_STYLE))
 }
 
-pub fn format_update_status_flags<'a>(update_status: &UpdateStatusWrapper) -> Text<'a> {
-    Text::from(match update_status.to_enum() {
+/* Files of mods that are no longer available on the Nexus are marked in addition to their update status, since deleting
+ * their archives might lose them for good. */
+pub fn format_flags<'a>(update_status: &UpdateStatusWrapper, mod_info: Option<&ModInfo>) -> Text<'a> {
+    let mut flags = vec![update_status_flag(update_status)];
+    if mod_info.is_some_and(|mi| mi.is_unavailable()) {
+        flags.push(Span::from("x").style(UNAVAILABLE_STYLE));
+    }
+    Text::from(Line::from(flags)).centered()
+}
+
+fn update_status_flag<'a>(update_status: &UpdateStatusWrapper) -> Span<'a> {
+    match update_status.to_enum() {
         UpdateStatus::OutOfDate(_) => Span::from("!").red(),
         UpdateStatus::UpToDate(_) => Span::from(""),
         UpdateStatus::IgnoredUntil(_) => Span::from(""),
+        UpdateStatus::IgnoredPermanently(_) => Span::from("-").dark_gray(),
         UpdateStatus::HasNewFile(_) => Span::from("+").yellow(),
         UpdateStatus::Invalid(_) => Span::from("?").yellow(),
-    })
-    .centered()
+    }
 }
@@ -1,20 +1,34 @@
 use ratatui::style::{Color, Style};
 use ratatui::text::Span;
-use ratatui::widgets::{Block, Borders, List, ListState};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 
 #[derive(Default)]
 pub struct ConfirmDialog<'a> {
     pub widget: List<'a>,
     pub state: ListState,
     pub len: usize,
+    // Rows taken by the choices and the details below them
+    pub height: usize,
 }
 
 impl<'a> ConfirmDialog<'a> {
     pub fn new(title: String) -> Self {
+        Self::with_details(title, vec![])
+    }
+
+    // The details are listed below the choices, which remain the only selectable items
+    pub fn with_details(title: String, details: Vec<String>) -> Self {
         let border_style = Style::default().fg(Color::Gray).bg(Color::Black);
         let block = Block::default().borders(Borders::ALL).title(title).border_style(border_style);
 
-        let widget = List::new([Span::raw("Ok"), Span::raw("Cancel")])
+        let mut items = vec![ListItem::new(Span::raw("Ok")), ListItem::new(Span::raw("Cancel"))];
+        if !details.is_empty() {
+            items.push(ListItem::new(""));
+            items.extend(details.into_iter().map(|line| ListItem::new(line).style(Style::default().fg(Color::Gray))));
+        }
+        let height = items.len();
+
+        let widget = List::new(items)
             .block(block)
             .style(Style::default())
             .highlight_style(Style::default().fg(Color::Black).bg(Color::Gray))
@@ -24,6 +38,7 @@ impl<'a> ConfirmDialog<'a> {
             state: ListState::default().with_selected(Some(0)),
             widget,
             len: 2,
+            height,
         }
     }
 }
@@ -40,7 +40,7 @@ impl<'a> DownloadsTable<'a> {
         let mut neighbors = NeighboringWidgets::new();
         neighbors
             .map
-            .insert(Tab::Archives, Neighbors::default().left(Focused::ArchiveTable));
+            .insert(Tab::Archives, Neighbors::default().left(Focused::ArchiveTable).down(Focused::Changelog));
 
         Self {
             headers,
@@ -37,11 +37,18 @@ fn create_widget<'a>(input_mode: &InputMode, focused: &Focused) -> Paragraph<'a>
         match input_mode {
             InputMode::Normal => match focused {
                 Focused::ArchiveTable => ARCHIVES_KEYS,
+                Focused::BrowseMods => BROWSE_KEYS,
+                Focused::Changelog => CHANGELOG_KEYS,
+                Focused::CollectionFiles => COLLECTION_KEYS,
                 Focused::InstalledMods => FILES_KEYS,
                 Focused::LogList => LOG_KEYS,
+                Focused::SearchFiles => SEARCH_FILES_KEYS,
+                Focused::SearchResults => SEARCH_KEYS,
+                Focused::TrackedMods => TRACKED_KEYS,
                 Focused::DownloadTable => DOWNLOADS_KEYS,
             },
             InputMode::ReadLine => INPUT_DIALOG_KEYS,
+            InputMode::Search => SEARCH_INPUT_KEYS,
             _ => &[],
         }
     };
@@ -37,7 +37,8 @@ impl<'a> InstalledModsTable<'a> {
             Cell::from(header_text("Version")),
         ]);
 
-        let neighbors = NeighboringWidgets::new();
+        let mut neighbors = NeighboringWidgets::new();
+        neighbors.map.insert(Tab::Installed, Neighbors::default().right(Focused::Changelog));
 
         Self {
             headers,
@@ -60,11 +61,16 @@ impl<'a> InstalledModsTable<'a> {
             self.currently_shown = lock.clone();
             for (i, (dir_name, dir_type)) in lock.iter().enumerate() {
                 let row = match dir_type {
-                    ModDirectory::Nexus(im) => Row::new(vec![
-                        Cell::new(Span::raw(dir_name.clone())),
-                        Cell::from(format_update_status_flags(&im.update_status)),
-                        Cell::from(Text::from(im.version.as_ref().map(|v| v.to_string()).unwrap_or("".to_string()))),
-                    ]),
+                    ModDirectory::Nexus(im) => {
+                        let mod_info = self.installed.mod_info(im.file_id).await;
+                        Row::new(vec![
+                            Cell::new(Span::raw(dir_name.clone())),
+                            Cell::from(format_flags(&im.update_status, mod_info.as_deref())),
+                            Cell::from(Text::from(
+                                im.version.as_ref().map(|v| v.to_string()).unwrap_or("".to_string()),
+                            )),
+                        ])
+                    }
                     _ => Row::new(vec![Span::raw((&dir_name).to_string())]),
                 }
                 .style(LIST_STYLES[i % 2]);
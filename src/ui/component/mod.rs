@@ -1,5 +1,8 @@
 mod archive_table;
 mod bottom_bar;
+mod browse_table;
+mod changelog_view;
+mod collection_table;
 pub mod common;
 mod confirm_dialog;
 mod download_table;
@@ -7,15 +10,24 @@ mod hotkey_bar;
 mod installed_mods_table;
 mod log_list;
 mod popup_dialog;
+mod search_files_table;
+mod search_table;
 mod topbar;
+mod tracked_mods_table;
 pub mod traits;
 
 pub use archive_table::ArchiveTable;
 pub use bottom_bar::BottomBar;
+pub use browse_table::BrowseTable;
+pub use changelog_view::ChangelogView;
+pub use collection_table::CollectionTable;
 pub use confirm_dialog::ConfirmDialog;
 pub use download_table::DownloadsTable;
 pub use hotkey_bar::HotkeyBar;
 pub use installed_mods_table::InstalledModsTable;
 pub use log_list::LogList;
 pub use popup_dialog::PopupDialog;
+pub use search_files_table::{ModFiles, SearchFilesTable};
+pub use search_table::{SearchResults, SearchTable};
 pub use topbar::TopBar;
+pub use tracked_mods_table::TrackedModsTable;
@@ -0,0 +1,104 @@
+use super::common::*;
+use crate::api::{FileDetails, FileList};
+use crate::ui::navigation::*;
+use ratatui::layout::Constraint;
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+// The files of the mod selected in the search results
+pub struct ModFiles {
+    pub game: String,
+    pub mod_id: u32,
+    pub file_list: Arc<FileList>,
+}
+
+pub struct SearchFilesTable<'a> {
+    headers: Row<'a>,
+    widths: [Constraint; 4],
+    pub files: Arc<RwLock<Option<ModFiles>>>,
+    pub has_changed: Arc<AtomicBool>,
+    pub currently_shown: Vec<Arc<FileDetails>>,
+    pub shown_mod: Option<(String, u32)>,
+    pub neighbors: NeighboringWidgets,
+    pub block: Block<'a>,
+    pub highlight_style: Style,
+    pub state: TableState,
+    pub widget: Table<'a>,
+    pub len: usize,
+}
+
+impl<'a> SearchFilesTable<'a> {
+    pub fn new() -> Self {
+        let block = DEFAULT_BLOCK.title(" Files ").border_style(BLOCK_STYLE);
+        let widths = [
+            Constraint::Ratio(6, 12),
+            Constraint::Ratio(2, 12),
+            Constraint::Ratio(2, 12),
+            Constraint::Ratio(2, 12),
+        ];
+
+        let headers = Row::new(vec![
+            Cell::from(header_text("Name")),
+            Cell::from(header_text("Version")),
+            Cell::from(header_text("Category")),
+            Cell::from(header_text("Size")),
+        ]);
+
+        let mut neighbors = NeighboringWidgets::new();
+        neighbors.map.insert(Tab::Search, Neighbors::default().left(Focused::SearchResults));
+
+        Self {
+            headers,
+            widths,
+            files: Default::default(),
+            has_changed: Arc::new(AtomicBool::new(true)),
+            currently_shown: vec![],
+            shown_mod: None,
+            neighbors,
+            block,
+            highlight_style: Style::default(),
+            state: TableState::default(),
+            widget: Table::default().widths(widths),
+            len: 0,
+        }
+    }
+
+    pub async fn refresh(&mut self) -> bool {
+        if self.has_changed.swap(false, Ordering::Relaxed) {
+            let mut rows: Vec<Row> = vec![];
+            self.currently_shown.clear();
+            self.shown_mod = None;
+            if let Some(mod_files) = &*self.files.read().await {
+                // Newest files first
+                for fd in mod_files.file_list.files.iter().rev() {
+                    let row = Row::new(vec![
+                        Cell::from(fd.name.clone()),
+                        Cell::from(fd.version.clone().unwrap_or_default()),
+                        Cell::from(fd.category_name.clone().unwrap_or_default()),
+                        Cell::from(format!("{:.1} MiB", fd.size as f64 / 1024.0)),
+                    ])
+                    .style(LIST_STYLES[rows.len() % 2]);
+                    rows.push(row);
+                    self.currently_shown.push(fd.clone());
+                }
+                self.shown_mod = Some((mod_files.game.clone(), mod_files.mod_id));
+            }
+
+            self.len = rows.len();
+
+            self.widget = Table::new(rows, self.widths)
+                .header(self.headers.to_owned())
+                .block(self.block.to_owned())
+                .row_highlight_style(self.highlight_style.to_owned());
+            return true;
+        }
+        false
+    }
+
+    pub fn get_by_index(&self, index: usize) -> &FileDetails {
+        self.currently_shown.get(index).unwrap()
+    }
+}
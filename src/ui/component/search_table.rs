@@ -0,0 +1,189 @@
+use super::common::*;
+use super::traits::Select;
+use crate::api::SearchResult;
+use crate::ui::navigation::*;
+use ratatui::layout::Constraint;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tui_textarea::TextArea;
+
+// All results arrive in one response, so they're paged locally
+const RESULTS_PER_PAGE: usize = 20;
+
+// The game the search was made for, and its results
+#[derive(Default)]
+pub struct SearchResults {
+    pub game: String,
+    pub results: Vec<SearchResult>,
+}
+
+pub struct SearchTable<'a> {
+    headers: Row<'a>,
+    widths: [Constraint; 4],
+    game: Option<String>,
+    page: usize,
+    pages: usize,
+    input_active: bool,
+    pub results: Arc<RwLock<SearchResults>>,
+    pub has_changed: Arc<AtomicBool>,
+    pub currently_shown: Vec<SearchResult>,
+    pub shown_game: String,
+    pub input: TextArea<'a>,
+    pub neighbors: NeighboringWidgets,
+    pub block: Block<'a>,
+    pub highlight_style: Style,
+    pub state: TableState,
+    pub widget: Table<'a>,
+    pub len: usize,
+}
+
+impl<'a> SearchTable<'a> {
+    pub fn new(game: Option<String>) -> Self {
+        let block = DEFAULT_BLOCK.title(" Search results ").border_style(BLOCK_STYLE);
+        let widths = [
+            Constraint::Ratio(6, 12),
+            Constraint::Ratio(2, 12),
+            Constraint::Ratio(2, 12),
+            Constraint::Ratio(2, 12),
+        ];
+
+        let headers = Row::new(vec![
+            Cell::from(header_text("Name")),
+            Cell::from(header_text("Author")),
+            Cell::from(header_text("Downloads")),
+            Cell::from(header_text("Endorsements")),
+        ]);
+
+        let mut input = TextArea::default();
+        input.set_cursor_line_style(Style::default());
+        input.set_placeholder_text("Press / to search");
+
+        let mut neighbors = NeighboringWidgets::new();
+        neighbors.map.insert(Tab::Search, Neighbors::default().right(Focused::SearchFiles));
+
+        let mut ret = Self {
+            headers,
+            widths,
+            game,
+            page: 0,
+            pages: 0,
+            input_active: false,
+            results: Default::default(),
+            has_changed: Arc::new(AtomicBool::new(true)),
+            currently_shown: vec![],
+            shown_game: String::new(),
+            input,
+            neighbors,
+            block,
+            highlight_style: Style::default(),
+            state: TableState::default(),
+            widget: Table::default().widths(widths),
+            len: 0,
+        };
+        ret.set_input_active(false);
+        ret
+    }
+
+    pub async fn refresh(&mut self) -> bool {
+        if self.has_changed.swap(false, Ordering::Relaxed) {
+            let results = self.results.read().await;
+            self.pages = results.results.len().div_ceil(RESULTS_PER_PAGE);
+            self.page = self.page.min(self.pages.saturating_sub(1));
+
+            let mut rows: Vec<Row> = vec![];
+            self.currently_shown.clear();
+            for result in results.results.iter().skip(self.page * RESULTS_PER_PAGE).take(RESULTS_PER_PAGE) {
+                let row = Row::new(vec![
+                    Cell::from(result.name.clone()),
+                    Cell::from(result.username.clone()),
+                    Cell::from(result.downloads.to_string()),
+                    Cell::from(result.endorsements.to_string()),
+                ])
+                .style(LIST_STYLES[rows.len() % 2]);
+                rows.push(row);
+                self.currently_shown.push(result.clone());
+            }
+            self.shown_game = results.game.clone();
+            self.len = rows.len();
+            drop(results);
+            // The selection can't be past the end of the shown page
+            let selected = self.selected();
+            self.select(selected);
+            self.set_input_active(self.input_active);
+
+            self.widget = Table::new(rows, self.widths)
+                .header(self.headers.to_owned())
+                .block(self.block.to_owned())
+                .row_highlight_style(self.highlight_style.to_owned());
+            return true;
+        }
+        false
+    }
+
+    pub fn get_by_index(&self, index: usize) -> &SearchResult {
+        self.currently_shown.get(index).unwrap()
+    }
+
+    pub fn next_page(&mut self) {
+        self.page += 1;
+        self.select(Some(0));
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn previous_page(&mut self) {
+        self.page = self.page.saturating_sub(1);
+        self.select(Some(0));
+        self.has_changed.store(true, Ordering::Relaxed);
+    }
+
+    // The results of a new search are shown from the first page
+    pub fn first_page(&mut self) {
+        self.page = 0;
+        self.state.select(None);
+    }
+
+    // The search box also shows which page of the results is shown
+    pub fn set_input_active(&mut self, active: bool) {
+        let mut title = match &self.game {
+            Some(game) => format!(" Search mods for {game} "),
+            None => " Set the game of the profile to search for mods ".to_string(),
+        };
+        if self.pages > 0 {
+            title.push_str(&format!("| Page {}/{} ", self.page + 1, self.pages));
+        }
+        self.input_active = active;
+        let (border_style, cursor_style) = match active {
+            true => (BLOCK_HIGHLIGHT_STYLE, Style::default().fg(Color::Black).bg(Color::Gray)),
+            false => (BLOCK_STYLE, Style::default()),
+        };
+        self.input.set_block(DEFAULT_BLOCK.title(title).border_style(border_style));
+        self.input.set_cursor_style(cursor_style);
+    }
+
+    pub fn search_terms(&self) -> String {
+        self.input.lines().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchTable;
+    use crate::ui::component::traits::Select;
+
+    #[tokio::test]
+    async fn page_empty_results() {
+        let mut table = SearchTable::new(Some("morrowind".to_string()));
+        table.refresh().await;
+
+        table.next_page();
+        table.refresh().await;
+        assert_eq!(None, table.selected());
+
+        table.previous_page();
+        table.refresh().await;
+        assert_eq!(None, table.selected());
+    }
+}
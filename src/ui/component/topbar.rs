@@ -1,4 +1,6 @@
-use crate::api::RequestCounter;
+use crate::api::{OfflineMode, RequestCounter, UpdateProgress};
+use crate::cache::Account;
+use crate::util::time;
 use ratatui::layout::Alignment;
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
@@ -9,37 +11,90 @@ pub struct TopBar<'a> {
     tab_titles: Vec<Line<'a>>,
     pub tabs_widget: Tabs<'a>,
     request_counter: RequestCounter,
+    offline: OfflineMode,
+    account: Account,
+    update_progress: UpdateProgress,
     pub counter_widget: Paragraph<'a>,
     needs_refresh: bool,
 }
 
 impl<'a> TopBar<'a> {
-    pub async fn new(request_counter: RequestCounter) -> Self {
+    pub async fn new(
+        request_counter: RequestCounter,
+        offline: OfflineMode,
+        account: Account,
+        update_progress: UpdateProgress,
+    ) -> Self {
         let highlight_style = Style::new().bg(Color::White).fg(Color::Black);
 
-        let tab_titles: Vec<Line<'a>> = vec!["Archives", "Installed", "Log"].into_iter().map(Line::from).collect();
+        let tab_titles: Vec<Line<'a>> = vec![
+            "Archives",
+            "Installed",
+            "Tracked",
+            "Search",
+            "Browse",
+            "Collection",
+            "Log",
+        ]
+        .into_iter()
+        .map(Line::from)
+        .collect();
         let tabs_widget = Tabs::new(tab_titles.clone()).highlight_style(highlight_style);
-        let counter_widget = Self::create_widget(&request_counter).await;
+        let counter_widget = Self::create_widget(&request_counter, &offline, &account, &update_progress).await;
 
         Self {
             tab_titles,
             tabs_widget,
             request_counter: request_counter.clone(),
+            offline,
+            account,
+            update_progress,
             counter_widget,
             needs_refresh: true,
         }
     }
 
-    pub async fn create_widget(request_counter: &RequestCounter) -> Paragraph<'a> {
+    pub async fn create_widget(
+        request_counter: &RequestCounter,
+        offline: &OfflineMode,
+        account: &Account,
+        update_progress: &UpdateProgress,
+    ) -> Paragraph<'a> {
         let counter = request_counter.counter.read().await;
-        let cells = vec![
+        let mut cells = vec![];
+        if let Some(user) = account.get().await {
+            cells.push(Span::from(format!("{} ", user.name)));
+            if user.is_premium {
+                cells.push(Span::styled("(Premium) ", Style::new().fg(Color::LightYellow)));
+            } else if user.is_supporter {
+                cells.push(Span::from("(Supporter) "));
+            }
+            cells.push(Span::from("| "));
+        }
+        if update_progress.is_running() {
+            let (checked, total, outdated) = update_progress.get();
+            cells.push(Span::from(format!("Checked {checked}/{total} mods, {outdated} outdated | ")));
+        }
+        // The counter isn't updated while offline, so it's replaced with the reason why
+        if offline.is_on() {
+            cells.push(Span::styled("Offline ", Style::new().fg(Color::LightYellow)));
+            return Paragraph::new(Line::from(cells)).alignment(Alignment::Right);
+        }
+        cells.extend([
             Span::from("API requests remaining: "),
             Span::from(format!(
                 " Hourly: {} | Daily: {}",
                 counter.hourly_remaining.map_or_else(|| "NA".to_string(), |i| i.to_string()),
                 counter.daily_remaining.map_or_else(|| "NA".to_string(), |i| i.to_string())
             )),
-        ];
+        ]);
+        let now = time::now();
+        if let Some(until) = counter.blocked_until(request_counter.reserve, now) {
+            cells.push(Span::styled(
+                format!(" | Paused for {}", time::human_readable_duration(until - now)),
+                Style::new().fg(Color::LightYellow),
+            ));
+        }
 
         Paragraph::new(Line::from(cells)).alignment(Alignment::Right)
     }
@@ -66,8 +121,19 @@ impl<'a> TopBar<'a> {
 
     pub async fn refresh(&mut self) -> bool {
         let mut ret = self.needs_refresh;
-        if self.request_counter.has_changed.swap(false, Ordering::Relaxed) {
-            self.counter_widget = Self::create_widget(&self.request_counter).await;
+        // The pause countdown needs to be redrawn even if the counter itself hasn't changed
+        let is_paused = self.request_counter.blocked_until().await.is_some();
+        let account_changed = self.account.has_changed.swap(false, Ordering::Relaxed);
+        let offline_changed = self.offline.has_changed.swap(false, Ordering::Relaxed);
+        let progress_changed = self.update_progress.has_changed.swap(false, Ordering::Relaxed);
+        if self.request_counter.has_changed.swap(false, Ordering::Relaxed)
+            || account_changed
+            || offline_changed
+            || progress_changed
+            || is_paused
+        {
+            self.counter_widget =
+                Self::create_widget(&self.request_counter, &self.offline, &self.account, &self.update_progress).await;
             ret = true;
         }
         ret
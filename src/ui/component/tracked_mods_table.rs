@@ -0,0 +1,88 @@
+use super::common::*;
+use crate::api::TrackedMod;
+use crate::ui::navigation::*;
+use crate::Cache;
+use ratatui::layout::Constraint;
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use std::sync::atomic::Ordering;
+
+// Lists the mods tracked on the Nexus that don't have any local files
+pub struct TrackedModsTable<'a> {
+    headers: Row<'a>,
+    widths: [Constraint; 3],
+    cache: Cache,
+    pub currently_shown: Vec<TrackedMod>,
+    pub neighbors: NeighboringWidgets,
+    pub block: Block<'a>,
+    pub highlight_style: Style,
+    pub state: TableState,
+    pub widget: Table<'a>,
+    pub len: usize,
+}
+
+impl<'a> TrackedModsTable<'a> {
+    pub fn new(cache: Cache) -> Self {
+        let block = DEFAULT_BLOCK.title(" Tracked, not downloaded ").border_style(BLOCK_STYLE);
+        let widths = [
+            Constraint::Ratio(2, 12),
+            Constraint::Ratio(1, 12),
+            Constraint::Ratio(9, 12),
+        ];
+
+        let headers = Row::new(vec![
+            Cell::from(header_text("Game")),
+            Cell::from(header_text("Mod id")),
+            Cell::from(header_text("Name")),
+        ]);
+
+        Self {
+            headers,
+            widths,
+            cache,
+            currently_shown: vec![],
+            neighbors: NeighboringWidgets::new(),
+            block,
+            highlight_style: Style::default(),
+            state: TableState::default(),
+            widget: Table::default().widths(widths),
+            len: 0,
+        }
+    }
+
+    pub async fn refresh(&mut self) -> bool {
+        if self.cache.tracked.has_changed.swap(false, Ordering::Relaxed) {
+            let mut rows: Vec<Row> = vec![];
+            self.currently_shown.clear();
+            let tracked = self.cache.tracked.mods.read().await.clone();
+            for tm in tracked {
+                if self.cache.metadata_index.get_modfiles(&tm.domain_name, &tm.mod_id).await.is_some() {
+                    continue;
+                }
+                // Only use cached mod info, looking up every tracked mod would use a lot of API requests
+                let name = self.cache.mod_info.get(&tm.domain_name, tm.mod_id).await.and_then(|mi| mi.name.clone());
+                let row = Row::new(vec![
+                    Cell::from(tm.domain_name.clone()),
+                    Cell::from(tm.mod_id.to_string()),
+                    Cell::from(name.unwrap_or_default()),
+                ])
+                .style(LIST_STYLES[rows.len() % 2]);
+                rows.push(row);
+                self.currently_shown.push(tm);
+            }
+
+            self.len = rows.len();
+
+            self.widget = Table::new(rows, self.widths)
+                .header(self.headers.to_owned())
+                .block(self.block.to_owned())
+                .row_highlight_style(self.highlight_style.to_owned());
+            return true;
+        }
+        false
+    }
+
+    pub fn get_by_index(&self, index: usize) -> &TrackedMod {
+        self.currently_shown.get(index).unwrap()
+    }
+}
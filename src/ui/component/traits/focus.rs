@@ -14,9 +14,15 @@ macro_rules! impl_focus {
 }
 
 impl_focus!(ArchiveTable<'_>);
+impl_focus!(BrowseTable<'_>);
+impl_focus!(ChangelogView<'_>);
+impl_focus!(CollectionTable<'_>);
 impl_focus!(DownloadsTable<'_>);
 impl_focus!(InstalledModsTable<'_>);
 impl_focus!(LogList<'_>);
+impl_focus!(SearchFilesTable<'_>);
+impl_focus!(SearchTable<'_>);
+impl_focus!(TrackedModsTable<'_>);
 
 pub trait Focus: Highlight + Select {
     fn neighbor_map(&self) -> &HashMap<Tab, Neighbors>;
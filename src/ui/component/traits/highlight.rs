@@ -1,5 +1,8 @@
 use crate::ui::component::common::*;
-use crate::ui::component::{ArchiveTable, DownloadsTable, InstalledModsTable, LogList};
+use crate::ui::component::{
+    ArchiveTable, BrowseTable, ChangelogView, CollectionTable, DownloadsTable, InstalledModsTable, LogList,
+    SearchFilesTable, SearchTable, TrackedModsTable,
+};
 use ratatui::style::Style;
 
 macro_rules! impl_table_highlight {
@@ -43,8 +46,14 @@ macro_rules! impl_highlight {
 }
 
 impl_table_highlight!(ArchiveTable<'_>);
+impl_table_highlight!(BrowseTable<'_>);
+impl_table_highlight!(CollectionTable<'_>);
 impl_table_highlight!(DownloadsTable<'_>);
 impl_table_highlight!(InstalledModsTable<'_>);
+impl_table_highlight!(SearchFilesTable<'_>);
+impl_table_highlight!(SearchTable<'_>);
+impl_table_highlight!(TrackedModsTable<'_>);
+impl_highlight!(ChangelogView<'_>);
 impl_highlight!(LogList<'_>);
 
 pub trait Highlight {
@@ -1,4 +1,7 @@
-use crate::ui::component::{ArchiveTable, ConfirmDialog, DownloadsTable, LogList, InstalledModsTable, PopupDialog};
+use crate::ui::component::{
+    ArchiveTable, BrowseTable, ChangelogView, CollectionTable, ConfirmDialog, DownloadsTable, InstalledModsTable,
+    LogList, PopupDialog, SearchFilesTable, SearchTable, TrackedModsTable,
+};
 
 macro_rules! impl_stateful {
     ($T:ty) => {
@@ -23,11 +26,17 @@ macro_rules! impl_stateful {
 }
 
 impl_stateful!(ArchiveTable<'_>);
+impl_stateful!(BrowseTable<'_>);
+impl_stateful!(ChangelogView<'_>);
+impl_stateful!(CollectionTable<'_>);
 impl_stateful!(ConfirmDialog<'_>);
 impl_stateful!(DownloadsTable<'_>);
 impl_stateful!(LogList<'_>);
 impl_stateful!(InstalledModsTable<'_>);
 impl_stateful!(PopupDialog<'_>);
+impl_stateful!(SearchFilesTable<'_>);
+impl_stateful!(SearchTable<'_>);
+impl_stateful!(TrackedModsTable<'_>);
 
 pub trait Select {
     fn len(&self) -> usize;
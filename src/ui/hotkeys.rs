@@ -1,32 +1,106 @@
 use super::component::traits::Select;
-use super::component::{ConfirmDialog, PopupDialog};
+use super::component::{ConfirmDialog, ModFiles, PopupDialog, SearchResults};
 use super::main_ui::*;
 use super::navigation::*;
+use crate::api::OFFLINE_MSG;
 use crate::cache::ArchiveEntry;
 use crate::extract::{InstallError, ModDirectory};
 use std::process::Command;
 use std::sync::atomic::Ordering;
 use termion::event::{Event, Key, MouseButton, MouseEvent};
 
+// Mods need to be installed for this long before they're endorsed in bulk
+const ENDORSE_AFTER_SECS: u64 = 7 * 24 * 60 * 60;
+
 pub const ARCHIVES_KEYS: &[(&str, &str)] = &[
     ("<Return>", "install "),
-    ("<u>", "update all "),
+    ("<c>", "compare with installed "),
+    ("<u>", "update all/cancel "),
     ("<U>", "update selected "),
     ("<i>", "ignore update "),
+    ("<I|M>", "ignore file/mod permanently "),
+    ("<n>", "unignore mod "),
+    ("<e>", "endorse/abstain "),
+    ("<E>", "endorse installed "),
+    ("<t>", "track/untrack "),
     ("<v>", "visit on Nexus "),
+    ("<r>", "show at risk/all "),
     ("<Del>", "delete "),
+    ("<o>", "offline/online "),
+    ("<q>", "quit "),
+];
+pub const BROWSE_KEYS: &[(&str, &str)] = &[
+    ("<[|]>", "previous/next list "),
+    ("<r>", "reload "),
+    ("<e>", "endorse/abstain "),
+    ("<t>", "track/untrack "),
+    ("<v>", "visit on Nexus "),
+    ("<o>", "offline/online "),
+    ("<q>", "quit "),
+];
+pub const CHANGELOG_KEYS: &[(&str, &str)] = &[("<j|k>", "scroll "), ("<o>", "offline/online "), ("<q>", "quit ")];
+pub const COLLECTION_KEYS: &[(&str, &str)] = &[
+    ("<Return>", "download (premium) / open on Nexus "),
+    ("<e>", "endorse/abstain "),
+    ("<t>", "track/untrack "),
+    ("<v>", "visit on Nexus "),
+    ("<o>", "offline/online "),
+    ("<q>", "quit "),
+];
+pub const DOWNLOADS_KEYS: &[(&str, &str)] = &[
+    ("<p>", "pause/resume "),
+    ("<Del>", "delete "),
+    ("<o>", "offline/online "),
     ("<q>", "quit "),
 ];
-pub const DOWNLOADS_KEYS: &[(&str, &str)] = &[("<p>", "pause/resume "), ("<Del>", "delete "), ("<q>", "quit ")];
 pub const FILES_KEYS: &[(&str, &str)] = &[
-    ("<u>", "update all "),
+    ("<u>", "update all/cancel "),
     ("<U>", "update selected "),
     ("<i>", "ignore update "),
+    ("<I|M>", "ignore file/mod permanently "),
+    ("<n>", "unignore mod "),
+    ("<g>", "upgrade "),
+    ("<e>", "endorse/abstain "),
+    ("<E>", "endorse installed "),
+    ("<t>", "track/untrack "),
     ("<v>", "visit on Nexus "),
     ("<Del>", "delete "),
+    ("<o>", "offline/online "),
+    ("<q>", "quit "),
+];
+pub const LOG_KEYS: &[(&str, &str)] = &[("<Del>", "delete "), ("<o>", "offline/online "), ("<q>", "quit ")];
+pub const TRACKED_KEYS: &[(&str, &str)] = &[
+    ("<t>", "untrack "),
+    ("<e>", "endorse/abstain "),
+    ("<v>", "visit on Nexus "),
+    ("<o>", "offline/online "),
+    ("<q>", "quit "),
+];
+pub const SEARCH_KEYS: &[(&str, &str)] = &[
+    ("</>", "search "),
+    ("<Return>", "show files "),
+    ("<[|]>", "previous/next page "),
+    ("<e>", "endorse/abstain "),
+    ("<t>", "track/untrack "),
+    ("<v>", "visit on Nexus "),
+    ("<o>", "offline/online "),
     ("<q>", "quit "),
 ];
-pub const LOG_KEYS: &[(&str, &str)] = &[("<Del>", "delete "), ("<q>", "quit ")];
+pub const SEARCH_FILES_KEYS: &[(&str, &str)] = &[
+    ("<Return>", "download (premium) "),
+    ("</>", "search "),
+    ("<e>", "endorse/abstain "),
+    ("<t>", "track/untrack "),
+    ("<v>", "visit on Nexus "),
+    ("<o>", "offline/online "),
+    ("<q>", "quit "),
+];
+pub const SEARCH_INPUT_KEYS: &[(&str, &str)] = &[
+    ("<Return>", "search "),
+    ("<Esc|C-c>", "cancel "),
+    ("<C-z>", "undo "),
+    ("<C-y>", "redo "),
+];
 pub const INPUT_DIALOG_KEYS: &[(&str, &str)] = &[
     ("<Return>", "confirm "),
     ("<Esc|C-c>", "cancel "),
@@ -49,6 +123,10 @@ impl MainUI<'_> {
             self.handle_popup_dialog(event).await;
             return;
         }
+        if let InputMode::Search = self.input_mode {
+            self.handle_search_input(event).await;
+            return;
+        }
         if let Event::Key(Key::Ctrl('c')) = event {
             self.should_run = false;
         }
@@ -118,29 +196,10 @@ impl MainUI<'_> {
                 self.previous_tab();
             }
             Event::Key(Key::Char('v')) => {
-                if let Some(i) = self.focused_widget().selected() {
-                    let mut args: Option<(String, u32)> = None;
-                    match self.nav.focused_widget() {
-                        Focused::ArchiveTable => {
-                            if let Some(metadata) = &self.archives_table.get_by_index(i).1.metadata() {
-                                args = Some((metadata.game.clone(), metadata.mod_id));
-                            }
-                        }
-                        Focused::InstalledMods => {
-                            let (_, md) = self.installed_mods_table.get_by_index(i);
-                            if let ModDirectory::Nexus(im) = md {
-                                args = Some((im.game.clone(), im.mod_id))
-                            }
-                        }
-                        _ => {
-                            return;
-                        }
-                    }
-                    if let Some((game, mod_id)) = args {
-                        let url = format!("https://www.nexusmods.com/{}/mods/{}", game, mod_id);
-                        if Command::new("xdg-open").arg(url).status().is_err() {
-                            self.logger.log("xdg-open is needed to open URLs in browser.".to_string());
-                        }
+                if let Some((game, mod_id)) = self.selected_mod() {
+                    let url = format!("https://www.nexusmods.com/{}/mods/{}", game, mod_id);
+                    if Command::new("xdg-open").arg(url).status().is_err() {
+                        self.logger.log("xdg-open is needed to open URLs in browser.".to_string());
                     }
                 }
             }
@@ -190,28 +249,39 @@ impl MainUI<'_> {
                         Focused::DownloadTable => {
                             self.downloads_table.delete_by_index(i).await;
                         }
+                        Focused::BrowseMods
+                        | Focused::Changelog
+                        | Focused::CollectionFiles
+                        | Focused::SearchFiles
+                        | Focused::SearchResults
+                        | Focused::TrackedMods => {}
                     }
                     // Ensure selected index isn't out of bounds after deletion
                     self.focused_widget_mut().select(Some(i));
                 }
             }
             Event::Key(Key::Char('i')) => {
-                if let Some(i) = self.focused_widget().selected() {
-                    match self.nav.focused_widget() {
-                        Focused::ArchiveTable => {
-                            let (_, archive) = self.archives_table.get_by_index(i);
-                            if let Some(metadata) = archive.metadata() {
-                                self.updater.ignore_file(metadata.file_id).await;
-                            }
-                        }
-                        Focused::InstalledMods => {
-                            let (_, mod_dir) = self.installed_mods_table.get_by_index(i);
-                            if let ModDirectory::Nexus(im) = mod_dir {
-                                self.updater.ignore_file(im.file_id).await;
-                            }
-                        }
-                        _ => {}
-                    }
+                if let Some(file_id) = self.selected_file_id() {
+                    self.updater.ignore_file(file_id).await;
+                }
+            }
+            Event::Key(Key::Char('I')) => {
+                if let Some(file_id) = self.selected_file_id() {
+                    self.updater.ignore_file_permanently(file_id).await;
+                }
+            }
+            Event::Key(Key::Char('M')) => {
+                if let (Some((game, mod_id)), Focused::ArchiveTable | Focused::InstalledMods) =
+                    (self.selected_mod(), self.nav.focused_widget())
+                {
+                    self.updater.ignore_mod_permanently(&game, mod_id).await;
+                }
+            }
+            Event::Key(Key::Char('n')) => {
+                if let (Some((game, mod_id)), Focused::ArchiveTable | Focused::InstalledMods) =
+                    (self.selected_mod(), self.nav.focused_widget())
+                {
+                    self.updater.unignore_mod(&game, mod_id).await;
                 }
             }
             Event::Key(Key::Char('U')) => {
@@ -241,7 +311,55 @@ impl MainUI<'_> {
                 }
             }
             Event::Key(Key::Char('u')) => {
-                self.updater.update_all().await;
+                if self.updater.progress.is_running() {
+                    self.updater.cancel();
+                } else {
+                    self.updater.update_all().await;
+                }
+            }
+            Event::Key(Key::Char('e')) => {
+                if let Some((game, mod_id)) = self.selected_mod() {
+                    let query = self.query.clone();
+                    let logger = self.logger.clone();
+                    let refresh_bottom_bar = self.bottom_bar.selected_has_changed.clone();
+                    tokio::task::spawn(async move {
+                        match query.toggle_endorsement(&game, mod_id).await {
+                            Ok(status) => logger.log(format!("Endorsement status of mod {mod_id}: {status:?}")),
+                            Err(e) => logger.log(format!("Failed to change endorsement of mod {mod_id}: {e}")),
+                        }
+                        refresh_bottom_bar.store(true, Ordering::Relaxed);
+                    });
+                }
+            }
+            Event::Key(Key::Char('E')) => {
+                // Endorsements are public, so they're only made once the user has confirmed them
+                if let Focused::ArchiveTable | Focused::InstalledMods = self.nav.focused_widget() {
+                    let to_endorse = self.query.installed_to_endorse(ENDORSE_AFTER_SECS).await;
+                    if to_endorse.is_empty() {
+                        self.logger.log("No mods installed for at least a week are left to endorse.");
+                    } else {
+                        let title = format!(" Endorse {} mods installed for at least a week? ", to_endorse.len());
+                        self.confirm_dialog = ConfirmDialog::new(title);
+                        self.confirmation = Some(Confirmation::EndorseInstalled(to_endorse));
+                        self.input_mode = InputMode::Confirm;
+                    }
+                }
+            }
+            Event::Key(Key::Char('t')) => {
+                if let Some((game, mod_id)) = self.selected_mod() {
+                    let query = self.query.clone();
+                    let logger = self.logger.clone();
+                    tokio::task::spawn(async move {
+                        match query.toggle_tracking(&game, mod_id).await {
+                            Ok(true) => logger.log(format!("Now tracking mod {mod_id}.")),
+                            Ok(false) => logger.log(format!("No longer tracking mod {mod_id}.")),
+                            Err(e) => logger.log(format!("Failed to change tracking of mod {mod_id}: {e}")),
+                        }
+                    });
+                }
+            }
+            Event::Key(Key::Char('o')) => {
+                self.toggle_offline();
             }
 
             _ => {
@@ -251,7 +369,7 @@ impl MainUI<'_> {
         }
         match self.nav.focused_widget() {
             Focused::InstalledMods => {
-                // no keys to handle
+                self.handle_installed_keys(event).await;
             }
             Focused::DownloadTable => {
                 self.handle_downloads_keys(event).await;
@@ -259,12 +377,84 @@ impl MainUI<'_> {
             Focused::ArchiveTable => {
                 self.handle_archives_keys(event).await;
             }
-            Focused::LogList => {
+            Focused::SearchFiles | Focused::SearchResults => {
+                self.handle_search_keys(event).await;
+            }
+            Focused::BrowseMods => {
+                self.handle_browse_keys(event).await;
+            }
+            Focused::CollectionFiles => {
+                self.handle_collection_keys(event).await;
+            }
+            Focused::Changelog | Focused::LogList | Focused::TrackedMods => {
                 // no keys to handle
             }
         }
     }
 
+    // The game and mod id of the selected archive, installed mod, tracked mod, search result or browsed mod
+    fn selected_mod(&self) -> Option<(String, u32)> {
+        let i = self.focused_widget().selected()?;
+        match self.nav.focused_widget() {
+            Focused::ArchiveTable => {
+                let metadata = self.archives_table.get_by_index(i).1.metadata()?;
+                Some((metadata.game.clone(), metadata.mod_id))
+            }
+            Focused::InstalledMods => match self.installed_mods_table.get_by_index(i).1 {
+                ModDirectory::Nexus(im) => Some((im.game.clone(), im.mod_id)),
+                _ => None,
+            },
+            Focused::TrackedMods => {
+                let tm = self.tracked_mods_table.get_by_index(i);
+                Some((tm.domain_name.clone(), tm.mod_id))
+            }
+            Focused::SearchResults => {
+                Some((self.search_table.shown_game.clone(), self.search_table.get_by_index(i).mod_id))
+            }
+            Focused::SearchFiles => self.search_files_table.shown_mod.clone(),
+            Focused::BrowseMods => {
+                let mi = self.browse_table.get_by_index(i);
+                Some((mi.domain_name.clone(), mi.mod_id))
+            }
+            Focused::CollectionFiles => {
+                let mf = self.collection_table.get_by_index(i);
+                Some((mf.game().to_string(), mf.mod_id()))
+            }
+            _ => None,
+        }
+    }
+
+    // The file id of the selected archive or installed mod
+    fn selected_file_id(&self) -> Option<u64> {
+        let i = self.focused_widget().selected()?;
+        match self.nav.focused_widget() {
+            Focused::ArchiveTable => self.archives_table.get_by_index(i).1.metadata().map(|metadata| metadata.file_id),
+            Focused::InstalledMods => match self.installed_mods_table.get_by_index(i).1 {
+                ModDirectory::Nexus(im) => Some(im.file_id),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // Out of date mods are upgraded to their replacement, which free users need to download from the Nexus
+    async fn handle_installed_keys(&mut self, event: Event) {
+        if let Event::Key(Key::Char('g')) = event {
+            let Some(i) = self.focused_widget().selected() else {
+                return;
+            };
+            let (dir_name, _) = self.installed_mods_table.get_by_index(i);
+            let (dir_name, downloads, logger) = (dir_name.clone(), self.downloads.clone(), self.logger.clone());
+            tokio::task::spawn(async move {
+                if let Some(url) = downloads.upgrade(&dir_name).await {
+                    if Command::new("xdg-open").arg(url).status().is_err() {
+                        logger.log("xdg-open is needed to open URLs in browser.".to_string());
+                    }
+                }
+            });
+        }
+    }
+
     async fn handle_downloads_keys(&mut self, event: Event) {
         let key = if let Event::Key(key) = event { key } else { return };
 
@@ -325,6 +515,31 @@ impl MainUI<'_> {
                     }
                 }
             }
+            Key::Char('c') => {
+                if let Some(i) = self.focused_widget().selected() {
+                    let (file_name, archive) = self.archives_table.get_by_index(i);
+                    let Some(metadata) = archive.metadata() else {
+                        self.logger.log(format!("{file_name} isn't from the Nexus, so its mod is unknown."));
+                        return;
+                    };
+                    let Some(dir_name) =
+                        self.cache.installed.find_by_mod(&metadata.game, metadata.mod_id, metadata.file_id).await
+                    else {
+                        self.logger.log(format!("No file of the mod of {file_name} is installed."));
+                        return;
+                    };
+                    let (file_name, installer) = (file_name.clone(), self.installer.clone());
+                    tokio::task::spawn(async move { installer.log_diff(&file_name, &dir_name).await });
+                }
+            }
+            Key::Char('r') => {
+                self.archives_table.toggle_at_risk_filter();
+                self.archives_table.refresh().await;
+                // The selection might be past the end of the filtered list
+                let selected = self.archives_table.selected();
+                self.archives_table.select(selected);
+                self.bottom_bar.selected_has_changed.store(true, Ordering::Relaxed);
+            }
             Key::Char('p') => {
                 if let Some(i) = self.focused_widget().selected() {
                     let (_, archive) = self.archives_table.get_by_index(i);
@@ -337,6 +552,181 @@ impl MainUI<'_> {
         }
     }
 
+    async fn handle_search_keys(&mut self, event: Event) {
+        let key = if let Event::Key(key) = event { key } else { return };
+
+        match key {
+            Key::Char('/') => {
+                self.input_mode = InputMode::Search;
+                self.search_table.set_input_active(true);
+                self.redraw_terminal = true;
+            }
+            Key::Char('[') => {
+                self.search_table.previous_page();
+            }
+            Key::Char(']') => {
+                self.search_table.next_page();
+            }
+            Key::Char('\n') => {
+                let Some(i) = self.focused_widget().selected() else {
+                    return;
+                };
+                match self.nav.focused_widget() {
+                    Focused::SearchResults => {
+                        let game = self.search_table.shown_game.clone();
+                        let mod_id = self.search_table.get_by_index(i).mod_id;
+                        let query = self.query.clone();
+                        let logger = self.logger.clone();
+                        let files = self.search_files_table.files.clone();
+                        let has_changed = self.search_files_table.has_changed.clone();
+                        tokio::task::spawn(async move {
+                            match query.file_list(&game, mod_id).await {
+                                Ok(file_list) => {
+                                    *files.write().await = Some(ModFiles {
+                                        game,
+                                        mod_id,
+                                        file_list,
+                                    });
+                                    has_changed.store(true, Ordering::Relaxed);
+                                }
+                                Err(e) => logger.log(format!("Unable to get the files of mod {mod_id}: {e}")),
+                            }
+                        });
+                        self.change_focus_to(Some(Focused::SearchFiles));
+                    }
+                    Focused::SearchFiles => {
+                        if let Some((game, mod_id)) = self.search_files_table.shown_mod.clone() {
+                            let file_id = self.search_files_table.get_by_index(i).file_id;
+                            let downloads = self.downloads.clone();
+                            tokio::task::spawn(async move {
+                                downloads.queue_premium(&game, mod_id, file_id).await;
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_browse_keys(&mut self, event: Event) {
+        let key = if let Event::Key(key) = event { key } else { return };
+
+        match key {
+            Key::Char('[') => self.browse_table.previous_list(),
+            Key::Char(']') => self.browse_table.next_list(),
+            Key::Char('r') => self.browse_table.reload(),
+            _ => {}
+        }
+    }
+
+    // Premium users download the selected file directly, others need to download it from its page on the Nexus
+    async fn handle_collection_keys(&mut self, event: Event) {
+        let key = if let Event::Key(key) = event { key } else { return };
+
+        if let Key::Char('\n') = key {
+            let Some(i) = self.focused_widget().selected() else {
+                return;
+            };
+            let mf = self.collection_table.get_by_index(i);
+            let (game, mod_id, file_id) = (mf.game().to_string(), mf.mod_id(), mf.file_id);
+            if self.cache.account.is_premium().await {
+                let downloads = self.downloads.clone();
+                tokio::task::spawn(async move {
+                    downloads.queue_premium(&game, mod_id, file_id).await;
+                });
+            } else {
+                let url = format!("https://www.nexusmods.com/{game}/mods/{mod_id}?tab=files&file_id={file_id}");
+                if Command::new("xdg-open").arg(url).status().is_err() {
+                    self.logger.log("xdg-open is needed to open URLs in browser.".to_string());
+                }
+            }
+        }
+    }
+
+    async fn handle_search_input(&mut self, event: Event) {
+        if let Event::Key(key) = event {
+            match key {
+                Key::Ctrl('c') | Key::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.search_table.set_input_active(false);
+                }
+                Key::Char('\n') => {
+                    self.input_mode = InputMode::Normal;
+                    self.search_table.set_input_active(false);
+                    self.search();
+                }
+                Key::Ctrl('z') => {
+                    self.search_table.input.undo();
+                }
+                Key::Ctrl('y') => {
+                    self.search_table.input.redo();
+                }
+                // disable tab character
+                Key::Char('\t') => {}
+                _ => {
+                    self.search_table.input.input(key);
+                }
+            }
+            self.redraw_terminal = true;
+        }
+    }
+
+    // nxm:// URLs received while offline are queued when going back online
+    fn toggle_offline(&mut self) {
+        if !self.offline.is_on() {
+            self.offline.set(true);
+            self.logger.log(OFFLINE_MSG);
+            return;
+        }
+        if self.config.apikey.is_none() {
+            self.logger.log("Going online requires an API key. Restart dmodman to create one.");
+            return;
+        }
+        if self.offline.is_apikey_rejected() {
+            self.logger.log("The Nexus rejected the API key. Restart dmodman to create a new one.");
+            return;
+        }
+        self.offline.set(false);
+        self.logger.log("Back online.");
+        let downloads = self.downloads.clone();
+        tokio::task::spawn(async move {
+            downloads.queue_pending().await;
+        });
+    }
+
+    fn search(&mut self) {
+        let Some(game) = self.config.game.clone() else {
+            self.logger.log("Set the game of the profile in config.toml to search for mods.");
+            return;
+        };
+        let terms = self.search_table.search_terms();
+        if terms.trim().is_empty() {
+            return;
+        }
+        self.search_table.first_page();
+        let query = self.query.clone();
+        let logger = self.logger.clone();
+        let results = self.search_table.results.clone();
+        let has_changed = self.search_table.has_changed.clone();
+        tokio::task::spawn(async move {
+            match query.search(&game, &terms).await {
+                Ok(search) => {
+                    if search.results.is_empty() {
+                        logger.log(format!("No mods found for \"{terms}\"."));
+                    }
+                    *results.write().await = SearchResults {
+                        game,
+                        results: search.results,
+                    };
+                    has_changed.store(true, Ordering::Relaxed);
+                }
+                Err(e) => logger.log(format!("Search failed: {e}")),
+            }
+        });
+    }
+
     async fn handle_confirm_dialog(&mut self, event: Event) {
         if let Event::Key(key) = event {
             match key {
@@ -349,7 +739,11 @@ impl MainUI<'_> {
                     self.redraw_terminal = true;
                 }
                 Key::Char('\n') => {
-                    if let 0 = self.confirm_dialog.selected().unwrap() {
+                    let accepted = self.confirm_dialog.selected() == Some(0);
+                    if let Some(confirmation) = self.confirmation.take() {
+                        self.confirm(confirmation, accepted);
+                        self.input_mode = InputMode::Normal;
+                    } else if accepted {
                         let dest_dir = self.popup_dialog.get_content();
                         let index = self.archives_table.selected().unwrap();
                         let (file_name, _archive) = self.archives_table.get_by_index(index);
@@ -364,7 +758,12 @@ impl MainUI<'_> {
                     self.redraw_terminal = true;
                 }
                 Key::Ctrl('c') | Key::Esc => {
-                    self.input_mode = InputMode::ReadLine;
+                    if let Some(confirmation) = self.confirmation.take() {
+                        self.confirm(confirmation, false);
+                        self.input_mode = InputMode::Normal;
+                    } else {
+                        self.input_mode = InputMode::ReadLine;
+                    }
                     self.redraw_terminal = true;
                 }
                 _ => {}
@@ -372,6 +771,29 @@ impl MainUI<'_> {
         }
     }
 
+    fn confirm(&self, confirmation: Confirmation, accepted: bool) {
+        match confirmation {
+            Confirmation::EndorseInstalled(to_endorse) => {
+                if !accepted {
+                    return;
+                }
+                let query = self.query.clone();
+                let logger = self.logger.clone();
+                let refresh_bottom_bar = self.bottom_bar.selected_has_changed.clone();
+                logger.log("Endorsing mods that have been installed for at least a week...");
+                tokio::task::spawn(async move {
+                    let count = query.endorse_installed(to_endorse).await;
+                    logger.log(format!("Endorsed {count} installed mods."));
+                    refresh_bottom_bar.store(true, Ordering::Relaxed);
+                });
+            }
+            Confirmation::Upgrade(upgrade) => {
+                let downloads = self.downloads.clone();
+                tokio::task::spawn(async move { downloads.finish_upgrade(upgrade, accepted).await });
+            }
+        }
+    }
+
     async fn handle_popup_dialog(&mut self, event: Event) {
         if let Event::Key(key) = event {
             match key {
@@ -1,7 +1,7 @@
 use super::component::traits::*;
 use super::component::*;
 use super::navigation::*;
-use crate::api::{Client, Downloads, Query, UpdateChecker};
+use crate::api::{Client, Downloads, OfflineMode, PendingUpgrade, Query, UpdateChecker};
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::extract::Installer;
@@ -10,13 +10,23 @@ use crate::ui::*;
 use crate::Logger;
 use ratatui::widgets::Clear;
 use ratatui::widgets::Paragraph;
+use std::collections::HashSet;
 use tokio::task;
 
+// The rest of the diff is only logged, so that the dialog fits on the screen
+const MAX_DIFF_LINES: usize = 15;
+
+pub enum Confirmation {
+    EndorseInstalled(HashSet<(String, u32)>),
+    Upgrade(PendingUpgrade),
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub enum InputMode {
     Normal,
     Confirm,
     ReadLine,
+    Search,
 }
 
 pub struct MainUI<'a> {
@@ -26,23 +36,32 @@ pub struct MainUI<'a> {
     pub config: Arc<Config>,
     pub downloads: Downloads,
     pub logger: Logger,
+    pub offline: OfflineMode,
     pub query: Query,
     pub updater: UpdateChecker,
 
     // UI widgets
     pub bottom_bar: BottomBar<'a>,
     pub archives_table: ArchiveTable<'a>,
+    pub browse_table: BrowseTable<'a>,
+    pub changelog_view: ChangelogView<'a>,
+    pub collection_table: CollectionTable<'a>,
     pub confirm_dialog: ConfirmDialog<'a>,
     pub downloads_table: DownloadsTable<'a>,
     pub installed_mods_table: InstalledModsTable<'a>,
     pub hotkey_bar: HotkeyBar<'a>,
     pub log_view: LogList<'a>,
     pub popup_dialog: PopupDialog<'a>,
+    pub search_files_table: SearchFilesTable<'a>,
+    pub search_table: SearchTable<'a>,
     pub top_bar: TopBar<'a>,
+    pub tracked_mods_table: TrackedModsTable<'a>,
 
     // UI state
     pub nav: Nav,
     pub input_mode: InputMode,
+    // What the confirm dialog is asking about, if it isn't about overwriting a directory
+    pub confirmation: Option<Confirmation>,
     pub should_run: bool,
     pub redraw_terminal: bool,
 }
@@ -56,7 +75,8 @@ impl MainUI<'_> {
         logger: Logger,
         query: Query,
     ) -> Self {
-        let installer = Installer::new(cache.clone(), config.clone(), logger.clone()).await;
+        // Shared with Downloads, which installs upgraded mods once their replacement has been downloaded
+        let installer = downloads.installer.clone();
         let updater = UpdateChecker::new(cache.clone(), client.clone(), config.clone(), logger.clone(), query.clone());
 
         let nav = Nav::new();
@@ -64,19 +84,33 @@ impl MainUI<'_> {
         let mut archives_table = ArchiveTable::new(cache.clone()).await;
         archives_table.add_highlight();
         let bottom_bar = BottomBar::new(cache.clone(), nav.focused_widget().clone());
+        let browse_table = BrowseTable::new(config.game.clone(), logger.clone(), query.clone());
+        let changelog_view =
+            ChangelogView::new(cache.clone(), logger.clone(), query.clone(), nav.focused_widget().clone());
+        let collection_table = CollectionTable::new(cache.clone(), downloads.clone());
         let confirm_dialog = ConfirmDialog::default();
         let downloads_table = DownloadsTable::new(downloads.clone());
         let files_table = InstalledModsTable::new(cache.installed.clone());
         let hotkey_bar = HotkeyBar::new(nav.focused_widget().clone());
         let log_list = LogList::new(logger.clone());
         let popup_dialog = PopupDialog::default();
-        let top_bar = TopBar::new(client.request_counter).await;
+        let search_files_table = SearchFilesTable::new();
+        let search_table = SearchTable::new(config.game.clone());
+        let top_bar = TopBar::new(
+            client.request_counter,
+            client.offline.clone(),
+            cache.account.clone(),
+            updater.progress.clone(),
+        )
+        .await;
+        let tracked_mods_table = TrackedModsTable::new(cache.clone());
 
         Self {
             cache,
             config,
             downloads,
             installer,
+            offline: client.offline,
             query,
             top_bar,
             hotkey_bar,
@@ -84,10 +118,17 @@ impl MainUI<'_> {
             installed_mods_table: files_table,
             downloads_table,
             log_view: log_list,
+            tracked_mods_table,
             bottom_bar,
+            browse_table,
+            changelog_view,
+            collection_table,
             confirm_dialog,
             popup_dialog,
+            search_files_table,
+            search_table,
             input_mode: InputMode::Normal,
+            confirmation: None,
             updater,
             logger,
             nav,
@@ -130,10 +171,10 @@ impl MainUI<'_> {
                             rectangles.recalculate_popup(self.popup_dialog.get_required_height(), frame.area());
                         }
                         if let InputMode::Confirm = self.input_mode {
-                            rectangles.recalculate_confirmdialog(self.confirm_dialog.len, frame.area());
+                            rectangles.recalculate_confirmdialog(self.confirm_dialog.height, frame.area());
                         }
                         match self.input_mode {
-                            InputMode::Normal => {
+                            InputMode::Normal | InputMode::Search => {
                                 match self.nav.selected().unwrap().into() {
                                     Tab::Archives => {
                                         frame.render_stateful_widget(
@@ -141,17 +182,77 @@ impl MainUI<'_> {
                                             rectangles.main_horizontal[0],
                                             &mut self.archives_table.state,
                                         );
+                                        if self.changelog_view.is_shown {
+                                            frame.render_stateful_widget(
+                                                &self.downloads_table.widget,
+                                                rectangles.archives_right[0],
+                                                &mut self.downloads_table.state,
+                                            );
+                                            frame.render_stateful_widget(
+                                                &self.changelog_view.widget,
+                                                rectangles.archives_right[1],
+                                                &mut self.changelog_view.state,
+                                            );
+                                        } else {
+                                            frame.render_stateful_widget(
+                                                &self.downloads_table.widget,
+                                                rectangles.main_horizontal[1],
+                                                &mut self.downloads_table.state,
+                                            );
+                                        }
+                                    }
+                                    Tab::Installed => {
+                                        if self.changelog_view.is_shown {
+                                            frame.render_stateful_widget(
+                                                &self.installed_mods_table.widget,
+                                                rectangles.main_horizontal[0],
+                                                &mut self.installed_mods_table.state,
+                                            );
+                                            frame.render_stateful_widget(
+                                                &self.changelog_view.widget,
+                                                rectangles.main_horizontal[1],
+                                                &mut self.changelog_view.state,
+                                            );
+                                        } else {
+                                            frame.render_stateful_widget(
+                                                &self.installed_mods_table.widget,
+                                                rectangles.main_vertical[2],
+                                                &mut self.installed_mods_table.state,
+                                            );
+                                        }
+                                    }
+                                    Tab::Tracked => {
                                         frame.render_stateful_widget(
-                                            &self.downloads_table.widget,
-                                            rectangles.main_horizontal[1],
-                                            &mut self.downloads_table.state,
+                                            &self.tracked_mods_table.widget,
+                                            rectangles.main_vertical[2],
+                                            &mut self.tracked_mods_table.state,
                                         );
                                     }
-                                    Tab::Installed => {
+                                    Tab::Search => {
+                                        frame.render_widget(&self.search_table.input, rectangles.search[0]);
+                                        frame.render_stateful_widget(
+                                            &self.search_table.widget,
+                                            rectangles.search_tables[0],
+                                            &mut self.search_table.state,
+                                        );
+                                        frame.render_stateful_widget(
+                                            &self.search_files_table.widget,
+                                            rectangles.search_tables[1],
+                                            &mut self.search_files_table.state,
+                                        );
+                                    }
+                                    Tab::Browse => {
+                                        frame.render_stateful_widget(
+                                            &self.browse_table.widget,
+                                            rectangles.main_vertical[2],
+                                            &mut self.browse_table.state,
+                                        );
+                                    }
+                                    Tab::Collection => {
                                         frame.render_stateful_widget(
-                                            &self.installed_mods_table.widget,
+                                            &self.collection_table.widget,
                                             rectangles.main_vertical[2],
-                                            &mut self.installed_mods_table.state,
+                                            &mut self.collection_table.state,
                                         );
                                     }
                                     Tab::Log => {
@@ -210,15 +311,62 @@ impl MainUI<'_> {
         }
     }
 
+    /* Upgrades wait for the user to accept the diff of the installed directory, including the ones started automatically
+     * once a replacement has been downloaded. They're asked about one at a time, when no other dialog is open. */
+    async fn show_pending_upgrade(&mut self) -> bool {
+        if !matches!(self.input_mode, InputMode::Normal) || self.confirmation.is_some() {
+            return false;
+        }
+        let Some(upgrade) = self.downloads.next_pending_upgrade().await else {
+            return false;
+        };
+        let mut details = vec![upgrade.archive_name.clone()];
+        match &upgrade.diff {
+            Some(diff) => {
+                let lines = diff.lines();
+                details.push(diff.to_string());
+                details.extend(lines.iter().take(MAX_DIFF_LINES).cloned());
+                if lines.len() > MAX_DIFF_LINES {
+                    details.push(format!("...and {} more, see the log.", lines.len() - MAX_DIFF_LINES));
+                }
+            }
+            None => details.push("The files couldn't be compared, see the log.".to_string()),
+        }
+        self.confirm_dialog = ConfirmDialog::with_details(format!(" Upgrade {}? ", upgrade.dir_name), details);
+        self.input_mode = InputMode::Confirm;
+        self.confirmation = Some(Confirmation::Upgrade(upgrade));
+        true
+    }
+
     // Returns true if self.redraw_terminal is true or any widget has changed
     async fn refresh_widgets(&mut self) -> bool {
+        let upgrade_dialog_shown = self.show_pending_upgrade().await;
         if self.nav.focused_tab() != Tab::Log && self.logger.has_changed.load(Ordering::Relaxed) {
             self.top_bar.add_urgency(Tab::Log.index());
         }
+        let changelog_has_changed = self
+            .changelog_view
+            .refresh(
+                &self.archives_table,
+                &self.installed_mods_table,
+                self.nav.focused_widget(),
+                self.focused_widget().selected(),
+            )
+            .await;
+        // The changelog can be hidden while another tab is shown
+        if !self.changelog_view.is_shown && *self.nav.focused_widget() == Focused::Changelog {
+            self.change_focus_to(self.changelog_view.neighbor_left(&self.nav.focused_tab()));
+        }
         self.redraw_terminal
+            | upgrade_dialog_shown
+            | changelog_has_changed
             | match self.nav.selected().unwrap().into() {
                 Tab::Archives => self.archives_table.refresh().await | self.downloads_table.refresh().await,
                 Tab::Installed => self.installed_mods_table.refresh().await,
+                Tab::Tracked => self.tracked_mods_table.refresh().await,
+                Tab::Search => self.search_table.refresh().await | self.search_files_table.refresh().await,
+                Tab::Browse => self.browse_table.refresh().await,
+                Tab::Collection => self.collection_table.refresh().await,
                 Tab::Log => self.log_view.refresh().await,
             }
             | self.top_bar.refresh().await
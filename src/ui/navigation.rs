@@ -1,12 +1,17 @@
 use super::component::traits::{Focus, Select};
 use super::main_ui::MainUI;
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 
 impl MainUI<'_> {
     pub fn select_tab(&mut self, index: usize) {
         if index < self.nav.focused_widget_per_tab.len() {
             self.focused_widget_mut().remove_highlight();
             self.nav.select(Some(index));
+            if let Tab::Tracked = self.nav.focused_tab() {
+                // Mods might have been downloaded or deleted since the view was last shown
+                self.cache.tracked.has_changed.store(true, Ordering::Relaxed);
+            }
             self.focused_widget_mut().add_highlight();
             self.top_bar.remove_urgency(index);
             self.top_bar.focus_tab(index);
@@ -29,23 +34,39 @@ impl MainUI<'_> {
     pub fn focused_widget(&self) -> &dyn Focus {
         match self.nav.focused_widget() {
             Focused::ArchiveTable => &self.archives_table,
+            Focused::BrowseMods => &self.browse_table,
+            Focused::Changelog => &self.changelog_view,
+            Focused::CollectionFiles => &self.collection_table,
             Focused::DownloadTable => &self.downloads_table,
             Focused::InstalledMods => &self.installed_mods_table,
             Focused::LogList => &self.log_view,
+            Focused::SearchFiles => &self.search_files_table,
+            Focused::SearchResults => &self.search_table,
+            Focused::TrackedMods => &self.tracked_mods_table,
         }
     }
 
     pub fn focused_widget_mut(&mut self) -> &mut dyn Focus {
         match &self.nav.focused_widget() {
             Focused::ArchiveTable => &mut self.archives_table,
+            Focused::BrowseMods => &mut self.browse_table,
+            Focused::Changelog => &mut self.changelog_view,
+            Focused::CollectionFiles => &mut self.collection_table,
             Focused::DownloadTable => &mut self.downloads_table,
             Focused::InstalledMods => &mut self.installed_mods_table,
             Focused::LogList => &mut self.log_view,
+            Focused::SearchFiles => &mut self.search_files_table,
+            Focused::SearchResults => &mut self.search_table,
+            Focused::TrackedMods => &mut self.tracked_mods_table,
         }
     }
 
     pub fn change_focus_to(&mut self, selected: Option<Focused>) {
         if let Some(selected) = selected {
+            // The changelog is only shown for files with updates
+            if selected == Focused::Changelog && !self.changelog_view.is_shown {
+                return;
+            }
             self.focused_widget_mut().remove_highlight();
             self.nav.set_focused_widget(selected);
             self.focused_widget_mut().add_highlight();
@@ -57,15 +78,25 @@ impl MainUI<'_> {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Focused {
     ArchiveTable,
+    BrowseMods,
+    Changelog,
+    CollectionFiles,
     DownloadTable,
     InstalledMods,
     LogList,
+    SearchFiles,
+    SearchResults,
+    TrackedMods,
 }
 
 #[derive(Eq, Hash, PartialEq)]
 pub enum Tab {
     Archives,
     Installed,
+    Tracked,
+    Search,
+    Browse,
+    Collection,
     Log,
 }
 
@@ -73,12 +104,20 @@ impl Tab {
     // Defines the order of the tabs
     const TAB_ARCHIVES: usize = 0;
     const TAB_INSTALLED: usize = 1;
-    const TAB_LOG: usize = 2;
+    const TAB_TRACKED: usize = 2;
+    const TAB_SEARCH: usize = 3;
+    const TAB_BROWSE: usize = 4;
+    const TAB_COLLECTION: usize = 5;
+    const TAB_LOG: usize = 6;
 
     pub fn index(&self) -> usize {
         match self {
             Tab::Archives => Self::TAB_ARCHIVES,
             Tab::Installed => Self::TAB_INSTALLED,
+            Tab::Tracked => Self::TAB_TRACKED,
+            Tab::Search => Self::TAB_SEARCH,
+            Tab::Browse => Self::TAB_BROWSE,
+            Tab::Collection => Self::TAB_COLLECTION,
             Tab::Log => Self::TAB_LOG,
         }
     }
@@ -95,7 +134,15 @@ impl Nav {
         Self {
             focused_tab: 0,
             // Default focused element for each tab
-            focused_widget_per_tab: vec![Focused::ArchiveTable, Focused::InstalledMods, Focused::LogList],
+            focused_widget_per_tab: vec![
+                Focused::ArchiveTable,
+                Focused::InstalledMods,
+                Focused::TrackedMods,
+                Focused::SearchResults,
+                Focused::BrowseMods,
+                Focused::CollectionFiles,
+                Focused::LogList,
+            ],
         }
     }
 
@@ -119,7 +166,18 @@ pub struct NeighboringWidgets {
 impl NeighboringWidgets {
     pub fn new() -> Self {
         Self {
-            map: HashMap::from([Tab::Archives, Tab::Installed, Tab::Log].map(|tab| (tab, Neighbors::default()))),
+            map: HashMap::from(
+                [
+                    Tab::Archives,
+                    Tab::Installed,
+                    Tab::Tracked,
+                    Tab::Search,
+                    Tab::Browse,
+                    Tab::Collection,
+                    Tab::Log,
+                ]
+                .map(|tab| (tab, Neighbors::default())),
+            ),
         }
     }
 }
@@ -184,6 +242,10 @@ impl From<usize> for Tab {
         match val {
             Self::TAB_ARCHIVES => Tab::Archives,
             Self::TAB_INSTALLED => Tab::Installed,
+            Self::TAB_TRACKED => Tab::Tracked,
+            Self::TAB_SEARCH => Tab::Search,
+            Self::TAB_BROWSE => Tab::Browse,
+            Self::TAB_COLLECTION => Tab::Collection,
             Self::TAB_LOG => Tab::Log,
             _ => unreachable!("Undefined tab index."),
         }
@@ -6,6 +6,8 @@ struct Layouts {
     top_bar: Layout,
     main_vertical: Layout,
     tables: Layout,
+    stacked: Layout,
+    search: Layout,
     bottom_bar: Layout,
 }
 
@@ -22,6 +24,15 @@ impl Layouts {
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)]);
 
+        let stacked = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)]);
+
+        let search = Layout::default().direction(Direction::Vertical).constraints([
+            Constraint::Length(3), // search box
+            Constraint::Fill(1),   // results and files
+        ]);
+
         let top_bar = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Ratio(1, 4), Constraint::Ratio(3, 4)])
@@ -36,6 +47,8 @@ impl Layouts {
             top_bar,
             main_vertical,
             tables,
+            stacked,
+            search,
             bottom_bar,
         }
     }
@@ -44,9 +57,12 @@ impl Layouts {
 pub struct Rectangles {
     layouts: Layouts,
     pub main_horizontal: Rc<[Rect]>,
+    pub archives_right: Rc<[Rect]>, // downloads and changelog
     pub main_vertical: Rc<[Rect]>,
     pub top_bar: Rc<[Rect]>,
     pub bottom_bar: Rc<[Rect]>,
+    pub search: Rc<[Rect]>,
+    pub search_tables: Rc<[Rect]>,
     pub confirm_dialog: Rc<[Rect]>,
     pub dialog_popup: Rc<[Rect]>,
     pub dialog_popup_input_line: Rc<[Rect]>,
@@ -61,6 +77,9 @@ impl Rectangles {
             top_bar: [Rect { ..Default::default() }].into(),
             bottom_bar: [Rect { ..Default::default() }].into(),
             main_horizontal: [Rect { ..Default::default() }].into(),
+            archives_right: [Rect { ..Default::default() }].into(),
+            search: [Rect { ..Default::default() }].into(),
+            search_tables: [Rect { ..Default::default() }].into(),
             confirm_dialog: [Rect { ..Default::default() }].into(),
             dialog_popup: [Rect { ..Default::default() }].into(),
             dialog_popup_input_line: [Rect { ..Default::default() }].into(),
@@ -71,7 +90,10 @@ impl Rectangles {
         self.top_bar = self.layouts.top_bar.split(window_size);
         self.main_vertical = self.layouts.main_vertical.split(window_size);
         self.main_horizontal = self.layouts.tables.split(self.main_vertical[2]);
+        self.archives_right = self.layouts.stacked.split(self.main_horizontal[1]);
         self.bottom_bar = self.layouts.bottom_bar.split(self.main_vertical[2]);
+        self.search = self.layouts.search.split(self.main_vertical[2]);
+        self.search_tables = self.layouts.tables.split(self.search[1]);
     }
 
     pub fn recalculate_popup(&mut self, list_height: usize, window_size: Rect) {
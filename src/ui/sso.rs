@@ -1,10 +1,11 @@
 use crate::api::sso::*;
+use crate::config::Config;
 use std::io::Write;
 use termion::event::Key;
 use termion::input::TermRead;
 use termion::raw::IntoRawMode;
 
-pub async fn start_apikey_flow() -> Option<String> {
+pub async fn start_apikey_flow(config: &Config) -> Option<String> {
     println!("dmodman requires an API key to work.");
     println!("Would you like to create one?");
     println!("[y]es, [n]o");
@@ -16,7 +17,7 @@ pub async fn start_apikey_flow() -> Option<String> {
 
     let mut sso_client;
     loop {
-        match SsoClient::new().await {
+        match SsoClient::new(config).await {
             Ok(c) => {
                 sso_client = c;
                 break;
@@ -37,6 +37,68 @@ pub fn human_readable(bytes: u64) -> (String, usize) {
     (format!("{:.*} {}", 1, bytes, units[i]), i)
 }
 
+/* Turns the HTML of changelogs and descriptions into plain text for the terminal. Line breaks, paragraphs and list
+ * items start new lines, other tags are dropped, and the common character references are decoded. */
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + len].trim().to_lowercase();
+        let name = tag.trim_start_matches('/').split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("");
+        match name {
+            "br" | "p" | "div" | "ul" | "ol" => text.push('\n'),
+            "li" if !tag.starts_with('/') => text.push_str("\n- "),
+            _ => {}
+        }
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
+
+    let text = decode_entities(&text);
+    let lines: Vec<&str> = text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+    lines.join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').filter(|end| *end <= 10).map(|end| &rest[1..end]);
+        let c = match entity {
+            Some("amp") => Some('&'),
+            Some("lt") => Some('<'),
+            Some("gt") => Some('>'),
+            Some("quot") => Some('"'),
+            Some("apos") => Some('\''),
+            Some("nbsp") => Some(' '),
+            Some(e) if e.starts_with("#x") || e.starts_with("#X") => {
+                u32::from_str_radix(&e[2..], 16).ok().and_then(char::from_u32)
+            }
+            Some(e) if e.starts_with('#') => e[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
 #[cfg(test)]
 mod tests {
     use crate::util::format;
@@ -57,4 +119,12 @@ mod tests {
         assert_eq!("936.7 MiB", format::human_readable(982232812).0);
         assert_eq!("19.9 GiB", format::human_readable(21402232812).0);
     }
+
+    #[test]
+    fn html_to_text() {
+        let html =
+            "<b>Fixed</b> a crash &amp; a typo<br />Added:<ul><li>OpenMW support</li><li>&quot;MCM&quot;</li></ul>";
+        assert_eq!("Fixed a crash & a typo\nAdded:\n- OpenMW support\n- \"MCM\"", format::html_to_text(html));
+        assert_eq!("Tom &amp Jerry 's < 3", format::html_to_text("Tom &amp Jerry &#39;s &lt; 3"));
+    }
 }
@@ -1,4 +1,6 @@
 pub mod format;
+pub mod time;
+pub mod version;
 
 use md5::{Digest, Md5};
 use std::path::PathBuf;
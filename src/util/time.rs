@@ -0,0 +1,94 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+// Modification time of a file in seconds since the Unix epoch
+pub fn modified(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/* Parses the timestamps found in the API's response headers into seconds since the Unix epoch.
+ * Nexus isn't consistent with the format, so both of these are accepted:
+ *     2024-05-28T15:00:00+00:00
+ *     2024-05-29 00:00:00 +0000 */
+pub fn parse_timestamp(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let date = s.get(0..10)?;
+    let time = s.get(11..19)?;
+    if !matches!(s.as_bytes().get(10), Some(b'T') | Some(b' ')) {
+        return None;
+    }
+    let offset = s.get(19..)?.trim();
+
+    let mut date_parts = date.split('-').map(str::parse::<i64>);
+    let (year, month, day) = (date_parts.next()?.ok()?, date_parts.next()?.ok()?, date_parts.next()?.ok()?);
+    let mut time_parts = time.split(':').map(str::parse::<i64>);
+    let (hour, min, sec) = (time_parts.next()?.ok()?, time_parts.next()?.ok()?, time_parts.next()?.ok()?);
+
+    let offset_secs = match offset {
+        "" | "Z" => 0,
+        _ => {
+            let sign = match offset.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+            if digits.len() != 4 {
+                return None;
+            }
+            sign * (digits[0..2].parse::<i64>().ok()? * 3600 + digits[2..4].parse::<i64>().ok()? * 60)
+        }
+    };
+
+    let secs = days_from_civil(year, month, day) * 86400 + hour * 3600 + min * 60 + sec - offset_secs;
+    u64::try_from(secs).ok()
+}
+
+/* Number of days since 1970-01-01 for a date in the proleptic Gregorian calendar.
+ * Howard Hinnant's algorithm: https://howardhinnant.github.io/date_algorithms.html#days_from_civil */
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// Formats a duration in seconds as a short human readable string, eg. "42s", "12m" or "3h 5m".
+pub fn human_readable_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m", secs.div_ceil(60))
+    } else {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::time;
+
+    #[test]
+    fn parse_timestamp() {
+        assert_eq!(Some(1716908400), time::parse_timestamp("2024-05-28T15:00:00+00:00"));
+        assert_eq!(Some(1716940800), time::parse_timestamp("2024-05-29 00:00:00 +0000"));
+        assert_eq!(Some(1716901200), time::parse_timestamp("2024-05-28T15:00:00+02:00"));
+        assert_eq!(Some(0), time::parse_timestamp("1970-01-01T00:00:00Z"));
+        assert_eq!(None, time::parse_timestamp("yesterday"));
+        assert_eq!(None, time::parse_timestamp("2024-05-28_15:00:00+00:00"));
+    }
+
+    #[test]
+    fn human_readable_duration() {
+        assert_eq!("42s", time::human_readable_duration(42));
+        assert_eq!("12m", time::human_readable_duration(690));
+        assert_eq!("3h 5m", time::human_readable_duration(11100));
+    }
+}
@@ -0,0 +1,72 @@
+use std::cmp::Ordering;
+
+/* Mod authors are free to version their mods however they like, so this is a best effort comparison.
+ * Versions are split into runs of digits and letters, which are compared numerically and alphabetically respectively.
+ * A leading "v" and any separators are ignored, as are trailing zeroes, so that "v1.2" equals "1.2.0". */
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let (a, b) = (components(a), components(b));
+    for i in 0..a.len().max(b.len()) {
+        let ord = match (a.get(i), b.get(i)) {
+            (Some(Component::Number(x)), Some(Component::Number(y))) => x.cmp(y),
+            (Some(Component::Text(x)), Some(Component::Text(y))) => x.cmp(y),
+            // Numbers are considered newer than text, e.g. 1.0.1 > 1.0b
+            (Some(Component::Number(_)), Some(Component::Text(_))) => Ordering::Greater,
+            (Some(Component::Text(_)), Some(Component::Number(_))) => Ordering::Less,
+            (Some(Component::Number(x)), None) => x.cmp(&0),
+            (None, Some(Component::Number(y))) => 0.cmp(y),
+            (Some(Component::Text(_)), None) => Ordering::Greater,
+            (None, Some(Component::Text(_))) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
+
+#[derive(Debug, PartialEq)]
+enum Component {
+    Number(u64),
+    Text(String),
+}
+
+fn components(version: &str) -> Vec<Component> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let mut components = vec![];
+    let mut chars = version.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            let mut number = c.to_string();
+            while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                number.push(d);
+            }
+            components.push(Component::Number(number.parse().unwrap_or(u64::MAX)));
+        } else if c.is_alphabetic() {
+            let mut text = c.to_lowercase().to_string();
+            while let Some(t) = chars.next_if(|t| t.is_alphabetic()) {
+                text.extend(t.to_lowercase());
+            }
+            components.push(Component::Text(text));
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn compare_versions() {
+        assert_eq!(Ordering::Less, compare("1.2", "1.10"));
+        assert_eq!(Ordering::Greater, compare("2.0", "1.9.9"));
+        assert_eq!(Ordering::Equal, compare("v1.2", "1.2.0"));
+        assert_eq!(Ordering::Less, compare("1.0b", "1.0c"));
+        assert_eq!(Ordering::Greater, compare("1.0.1", "1.0b"));
+        assert_eq!(Ordering::Less, compare("1.0", "1.0a"));
+        assert_eq!(Ordering::Equal, compare("1-04", "1.04"));
+    }
+}